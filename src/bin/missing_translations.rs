@@ -0,0 +1,97 @@
+//! List the localization keys without text in each language, exits with 1 if
+//! any is missing.
+//!
+//! cargo run --bin missing_translations -- resources/game
+
+use serde::de::DeserializeOwned;
+use std::{
+    collections::{BTreeSet, HashMap},
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+use tower::core::{
+    data_keys, script_keys, Action, Character, CharacterTemplate, Item, Localization, NovelSystem,
+    PlayerClass, Quest,
+};
+
+fn load<T: DeserializeOwned>(path: &Path) -> T {
+    let source = fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path.display(), e);
+        process::exit(2);
+    });
+    ron::de::from_str(&source).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path.display(), e);
+        process::exit(2);
+    })
+}
+
+/// Files of the dir with the extension, by file stem
+fn files<'a>(dir: &'a Path, extension: &'a str) -> impl Iterator<Item = (String, PathBuf)> + 'a {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(move |path| path.extension().and_then(|e| e.to_str()) == Some(extension))
+        .filter_map(|path| Some((String::from(path.file_stem()?.to_str()?), path)))
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 2 {
+        eprintln!("Usage: {} <game dir>", args[0]);
+        process::exit(2);
+    }
+    let dir = Path::new(&args[1]);
+
+    let mut localization: Localization = load(&dir.join("localization.ron"));
+    for (language, path) in files(&dir.join("lang"), "ron") {
+        localization.tables.insert(language, load(&path));
+    }
+
+    let mut novel_system: NovelSystem = load(&dir.join("novel_system.ron"));
+    let scripts = dir.join("scripts");
+    let paths = files(&scripts, "script").map(|(_, path)| path);
+    let errors = novel_system.load_scripts(paths, |path| {
+        fs::read_to_string(path).map_err(|e| e.to_string())
+    });
+    if !errors.is_empty() {
+        for e in errors {
+            eprintln!("{}", e);
+        }
+        process::exit(2);
+    }
+
+    let actions: HashMap<i32, Action> = load(&dir.join("actions.ron"));
+    let items: HashMap<i32, Item> = load(&dir.join("items.ron"));
+    let characters: HashMap<i32, Character> = load(&dir.join("characters.ron"));
+    let character_templates: HashMap<i32, CharacterTemplate> =
+        load(&dir.join("character_templates.ron"));
+    let quests: HashMap<i32, Quest> = load(&dir.join("quests.ron"));
+    let player_classes: HashMap<i32, PlayerClass> = load(&dir.join("player_classes.ron"));
+
+    let mut keys = BTreeSet::new();
+    data_keys(
+        actions.values(),
+        items.values(),
+        characters.values(),
+        character_templates.values(),
+        quests.values(),
+        player_classes.values(),
+        &mut keys,
+    );
+    for script in novel_system.scripts.values() {
+        script_keys(&script.scripts, &mut keys);
+    }
+
+    let mut found = false;
+    for (language, missing) in localization.missing(&keys) {
+        for key in missing {
+            println!("{}: {}", language, key);
+            found = true;
+        }
+    }
+    if found {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,67 @@
+//! Report problems in the novel scripts, one per line, exits with 1 if any is
+//! found.
+//!
+//! cargo run --bin novel_check -- resources/game
+
+use serde::de::DeserializeOwned;
+use std::{collections::HashMap, env, fs, path::Path, process};
+use tower::core::{analyze_scripts, Character, CharacterTemplate, NovelSystem, Tile, TileMap};
+
+fn load<T: DeserializeOwned>(path: &Path) -> T {
+    let source = fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path.display(), e);
+        process::exit(2);
+    });
+    ron::de::from_str(&source).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path.display(), e);
+        process::exit(2);
+    })
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 2 {
+        eprintln!("Usage: {} <game dir>", args[0]);
+        process::exit(2);
+    }
+    let dir = Path::new(&args[1]);
+
+    let mut novel_system: NovelSystem = load(&dir.join("novel_system.ron"));
+    let paths = fs::read_dir(dir.join("scripts"))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()));
+    let errors = novel_system.load_scripts(paths, |path| {
+        fs::read_to_string(path).map_err(|e| e.to_string())
+    });
+    if !errors.is_empty() {
+        for e in errors {
+            eprintln!("{}", e);
+        }
+        process::exit(2);
+    }
+    let characters: HashMap<i32, Character> = load(&dir.join("characters.ron"));
+    let character_templates: HashMap<i32, CharacterTemplate> =
+        load(&dir.join("character_templates.ron"));
+    let tile_maps: HashMap<i32, TileMap> = load(&dir.join("tile_maps.ron"));
+    let tiles: HashMap<i32, Tile> = load(&dir.join("tiles.ron"));
+
+    let issues = analyze_scripts(
+        &novel_system,
+        &characters,
+        &character_templates,
+        &tile_maps,
+        &tiles,
+    );
+    for issue in &issues {
+        println!("{}", issue);
+    }
+    if !issues.is_empty() {
+        println!(
+            "{} issue(s) in {} script(s)",
+            issues.len(),
+            novel_system.scripts.len()
+        );
+        process::exit(1);
+    }
+}
@@ -0,0 +1,32 @@
+//! Export the RON novel scripts to the text format, one `<id>.script` file
+//! per script.
+//!
+//! cargo run --bin novel_export -- resources/game/novel_system.ron resources/game/scripts
+
+use std::{env, fs, path::PathBuf, process};
+use tower::core::NovelSystem;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        eprintln!("Usage: {} <novel_system.ron> <output dir>", args[0]);
+        process::exit(2);
+    }
+    let source = fs::read_to_string(&args[1]).expect("Could not read novel system");
+    let novel_system: NovelSystem = match ron::de::from_str(&source) {
+        Ok(novel_system) => novel_system,
+        Err(e) => {
+            eprintln!("{}: {}", args[1], e);
+            process::exit(1);
+        }
+    };
+    let dir = PathBuf::from(&args[2]);
+    fs::create_dir_all(&dir).expect("Could not create output dir");
+    let mut ids: Vec<_> = novel_system.scripts.keys().collect();
+    ids.sort();
+    for id in ids {
+        let path = dir.join(format!("{}.script", id));
+        fs::write(&path, novel_system.scripts[id].export()).expect("Could not write script");
+        println!("{}", path.display());
+    }
+}
@@ -6,6 +6,8 @@ use super::{sprite::Animation, Game, Position};
 pub struct Action {
     pub id: i32,
     pub name: String,
+    /// Localization keys
+    pub label: String,
     pub description: String,
     pub hp: Option<i32>,
     pub mp: Option<i32>,
@@ -14,6 +16,8 @@ pub struct Action {
     pub effect_kind: EffectKind,
     pub range: i32,
     pub duration: f32,
+    /// Turn time the action costs, slow actions push the next turn back
+    pub turn_time: i32,
     pub cooldown: i32,
     pub animation: Option<Animation>,
 }
@@ -32,7 +36,7 @@ pub enum ActionKind {
     MpRecover(i32),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
 pub enum Target {
     None,
     Character(i32),
@@ -84,7 +88,7 @@ impl EffectKind {
                     Target::Character(id) => game.characters.get(id).map(|c| c.position).unwrap(),
                     Target::Position(p) => *p,
                 };
-                game.characters_in_square(&position, range)
+                in_line_of_fire(game, &position, game.characters_in_square(&position, range))
             }
             EffectKind::Manhattan(range) => {
                 let position = match target {
@@ -96,8 +100,21 @@ impl EffectKind {
                     Target::Character(id) => game.characters.get(id).map(|c| c.position).unwrap(),
                     Target::Position(p) => *p,
                 };
-                game.characters_in_range(&position, range)
+                in_line_of_fire(game, &position, game.characters_in_range(&position, range))
             }
         }
     }
 }
+
+/// Area effects do not spread through tiles that block projectiles
+fn in_line_of_fire(game: &Game, position: &Position, character_ids: Vec<i32>) -> Vec<i32> {
+    character_ids
+        .into_iter()
+        .filter(|id| {
+            game.characters
+                .get(id)
+                .map(|c| game.line_of_fire(position, &c.position))
+                .unwrap_or(false)
+        })
+        .collect()
+}
@@ -0,0 +1,489 @@
+use super::{
+    Character, CharacterTemplate, Condition, NovelSystem, Position, ScriptCharacter, ScriptKind,
+    Tile, TileMap, BOSS_MAP_ID, BOSS_TALK_ID,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+/// Problem found in a novel script
+#[derive(Debug)]
+pub struct ScriptIssue {
+    pub script_id: i32,
+    /// Path of the instruction, as in `CurrentScript.p`, empty for the script
+    pub path: Vec<usize>,
+    pub kind: ScriptIssueKind,
+}
+
+#[derive(Debug)]
+pub enum ScriptIssueKind {
+    /// Follows an End, Restart or Goto with no label jumped to in between
+    AfterJump,
+    /// Branch of a condition that can never be met, or else branch of one
+    /// that is always met
+    NeverMet,
+    /// Select option with no instruction and nothing after the Select, picking
+    /// it silently ends the conversation
+    EmptyOption(String),
+    /// ForgetSkill without a SelectAbility before it, it does nothing
+    ForgetSkillWithoutSelectAbility,
+    /// NotWalkable(map_id, x, y), CharacterMove onto a tile no one can stand on
+    NotWalkable(i32, i32, i32),
+    /// No character talk_id or dialogue, floor event or Call starts the script
+    Unreferenced,
+}
+
+impl fmt::Display for ScriptIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "script {} {:?}: ", self.script_id, self.path)?;
+        match &self.kind {
+            ScriptIssueKind::AfterJump => write!(f, "unreachable after End, Restart or Goto"),
+            ScriptIssueKind::NeverMet => write!(f, "unreachable branch, condition is constant"),
+            ScriptIssueKind::EmptyOption(text) => write!(f, "option {:?} has no instruction", text),
+            ScriptIssueKind::ForgetSkillWithoutSelectAbility => {
+                write!(f, "ForgetSkill without a SelectAbility before it")
+            }
+            ScriptIssueKind::NotWalkable(map_id, x, y) => write!(
+                f,
+                "CharacterMove to ({}, {}) which is not walkable on map {}",
+                x, y, map_id
+            ),
+            ScriptIssueKind::Unreferenced => write!(f, "never started"),
+        }
+    }
+}
+
+/// State flowing through the instructions of a block
+#[derive(Clone, Default)]
+struct Flow {
+    ability_selected: bool,
+    /// Maps the characters were moved to by CharacterChangePosition
+    maps: HashMap<ScriptCharacter, i32>,
+}
+
+struct Analyzer<'a> {
+    novel_system: &'a NovelSystem,
+    characters: &'a HashMap<i32, Character>,
+    tile_maps: &'a HashMap<i32, TileMap>,
+    tiles: &'a HashMap<i32, Tile>,
+    /// Maps where the talking characters of a script stand, by script id
+    talk_maps: HashMap<i32, HashSet<i32>>,
+    /// Labels jumped to in the script being analyzed
+    gotos: HashSet<String>,
+    issues: Vec<ScriptIssue>,
+}
+
+/// Find unreachable instructions, empty options, misplaced ForgetSkill, moves
+/// to non walkable tiles and scripts never started, sorted by script and path
+pub fn analyze_scripts(
+    novel_system: &NovelSystem,
+    characters: &HashMap<i32, Character>,
+    character_templates: &HashMap<i32, CharacterTemplate>,
+    tile_maps: &HashMap<i32, TileMap>,
+    tiles: &HashMap<i32, Tile>,
+) -> Vec<ScriptIssue> {
+    let mut talk_maps: HashMap<i32, HashSet<i32>> = HashMap::new();
+    for c in characters.values() {
+        let dialogues = c.dialogues.iter().map(|d| d.talk_id);
+        for talk_id in dialogues.chain(Some(c.talk_id)) {
+            talk_maps
+                .entry(talk_id)
+                .or_default()
+                .insert(c.position.map_id);
+        }
+    }
+    talk_maps
+        .entry(BOSS_TALK_ID)
+        .or_default()
+        .insert(BOSS_MAP_ID);
+
+    let mut referenced: HashSet<i32> = talk_maps.keys().copied().collect();
+    for t in character_templates.values() {
+        referenced.insert(t.talk_id);
+        referenced.extend(t.dialogues.iter().map(|d| d.talk_id));
+    }
+    for script in novel_system.scripts.values() {
+        calls(&script.scripts, &mut referenced);
+    }
+
+    let mut analyzer = Analyzer {
+        novel_system,
+        characters,
+        tile_maps,
+        tiles,
+        talk_maps,
+        gotos: HashSet::new(),
+        issues: Vec::new(),
+    };
+    for (id, script) in &novel_system.scripts {
+        if !referenced.contains(id) {
+            analyzer.issue(*id, Vec::new(), ScriptIssueKind::Unreferenced);
+        }
+        analyzer.gotos.clear();
+        gotos(&script.scripts, &mut analyzer.gotos);
+        analyzer.block(*id, &script.scripts, &[], true, Flow::default());
+    }
+    let mut issues = analyzer.issues;
+    issues.sort_by(|a, b| (a.script_id, &a.path).cmp(&(b.script_id, &b.path)));
+    issues
+}
+
+/// Scripts called from the instructions
+fn calls(scripts: &[ScriptKind], ids: &mut HashSet<i32>) {
+    for s in scripts {
+        if let ScriptKind::Call(id) = s {
+            ids.insert(*id);
+        }
+        let mut b = 0;
+        while let Some(v) = s.branch(b) {
+            calls(v, ids);
+            b += 1;
+        }
+    }
+}
+
+/// Labels jumped to from the instructions
+fn gotos(scripts: &[ScriptKind], labels: &mut HashSet<String>) {
+    for s in scripts {
+        if let ScriptKind::Goto(label) = s {
+            labels.insert(label.clone());
+        }
+        let mut b = 0;
+        while let Some(v) = s.branch(b) {
+            gotos(v, labels);
+            b += 1;
+        }
+    }
+}
+
+impl<'a> Analyzer<'a> {
+    fn issue(&mut self, script_id: i32, path: Vec<usize>, kind: ScriptIssueKind) {
+        self.issues.push(ScriptIssue {
+            script_id,
+            path,
+            kind,
+        });
+    }
+
+    /// Analyze a block, `prefix` is the path of the block and `ends` whether
+    /// the conversation ends after it
+    fn block(
+        &mut self,
+        script_id: i32,
+        scripts: &[ScriptKind],
+        prefix: &[usize],
+        ends: bool,
+        mut flow: Flow,
+    ) {
+        let mut reachable = true;
+        // Report only the first instruction of an unreachable run
+        let mut reported = false;
+        for (i, s) in scripts.iter().enumerate() {
+            let path = [prefix, &[i]].concat();
+            if let ScriptKind::Label(label) = s {
+                reachable = reachable || self.gotos.contains(label);
+            }
+            if !reachable {
+                if !reported {
+                    self.issue(script_id, path, ScriptIssueKind::AfterJump);
+                    reported = true;
+                }
+                continue;
+            }
+            reported = false;
+            let last = ends && i + 1 == scripts.len();
+            self.instruction(script_id, s, &path, last, &mut flow);
+            if let ScriptKind::End | ScriptKind::Restart | ScriptKind::Goto(_) = s {
+                reachable = false;
+            }
+        }
+    }
+
+    fn instruction(
+        &mut self,
+        script_id: i32,
+        s: &ScriptKind,
+        path: &[usize],
+        last: bool,
+        flow: &mut Flow,
+    ) {
+        // Whether each branch can run
+        let branches = match s {
+            ScriptKind::If(condition, _) => vec![self.constant(condition) != Some(false)],
+            ScriptKind::IfElse(condition, _, _) => {
+                let constant = self.constant(condition);
+                vec![constant != Some(false), constant != Some(true)]
+            }
+            ScriptKind::Select(options) => options
+                .iter()
+                .map(|option| {
+                    let visible = option.visible.as_ref().and_then(|c| self.constant(c));
+                    let enabled = option.enabled.as_ref().and_then(|(c, _)| self.constant(c));
+                    visible != Some(false) && enabled != Some(false)
+                })
+                .collect(),
+            ScriptKind::SelectAbility(_) => vec![true],
+            _ => Vec::new(),
+        };
+        match s {
+            ScriptKind::ForgetSkill if !flow.ability_selected => self.issue(
+                script_id,
+                path.to_vec(),
+                ScriptIssueKind::ForgetSkillWithoutSelectAbility,
+            ),
+            ScriptKind::CharacterMove(c, x, y) => {
+                for map_id in self.maps(script_id, c, flow) {
+                    let position = Position {
+                        map_id,
+                        x: *x,
+                        y: *y,
+                    };
+                    if !self.is_walkable(&position) {
+                        self.issue(
+                            script_id,
+                            path.to_vec(),
+                            ScriptIssueKind::NotWalkable(map_id, *x, *y),
+                        );
+                    }
+                }
+            }
+            ScriptKind::CharacterChangePosition(c, _, _, map_id) => {
+                flow.maps.insert(c.clone(), *map_id);
+            }
+            _ => {}
+        }
+        for (b, reachable) in branches.into_iter().enumerate() {
+            let v = match s.branch(b) {
+                Some(v) => v,
+                None => continue,
+            };
+            let prefix = [path, &[b]].concat();
+            if !reachable {
+                self.issue(script_id, prefix, ScriptIssueKind::NeverMet);
+                continue;
+            }
+            if let ScriptKind::Select(options) = s {
+                if v.is_empty() && last {
+                    let text = options[b].text.clone();
+                    self.issue(
+                        script_id,
+                        prefix.clone(),
+                        ScriptIssueKind::EmptyOption(text),
+                    );
+                }
+            }
+            let mut branch_flow = flow.clone();
+            if let ScriptKind::SelectAbility(_) = s {
+                branch_flow.ability_selected = true;
+            }
+            self.block(script_id, v, &prefix, last, branch_flow);
+        }
+        if let ScriptKind::SelectAbility(_) = s {
+            flow.ability_selected = true;
+        }
+    }
+
+    /// Value of a condition that does not depend on the game, if any
+    fn constant(&self, condition: &Condition) -> Option<bool> {
+        match condition {
+            Condition::And(v) => {
+                let values: Vec<_> = v.iter().map(|c| self.constant(c)).collect();
+                if values.contains(&Some(false)) {
+                    Some(false)
+                } else if values.iter().all(|c| *c == Some(true)) {
+                    Some(true)
+                } else {
+                    None
+                }
+            }
+            Condition::Or(v) => {
+                let values: Vec<_> = v.iter().map(|c| self.constant(c)).collect();
+                if values.contains(&Some(true)) {
+                    Some(true)
+                } else if values.iter().all(|c| *c == Some(false)) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            Condition::Not(c) => self.constant(c).map(|c| !c),
+            // Never met if the script has no such option
+            Condition::Selected(script_id, text) => {
+                let offered = self
+                    .novel_system
+                    .scripts
+                    .get(script_id)
+                    .map(|script| has_option(&script.scripts, text))
+                    .unwrap_or(false);
+                if offered {
+                    None
+                } else {
+                    Some(false)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Maps the character can be on when the instruction runs. The initiator
+    /// talks to the target so both are on the map of the talking characters.
+    fn maps(&self, script_id: i32, c: &ScriptCharacter, flow: &Flow) -> Vec<i32> {
+        if let Some(map_id) = flow.maps.get(c) {
+            return vec![*map_id];
+        }
+        match c {
+            ScriptCharacter::Character(id) => self
+                .characters
+                .get(id)
+                .map(|c| c.position.map_id)
+                .into_iter()
+                .collect(),
+            ScriptCharacter::Initiator | ScriptCharacter::Target => {
+                let mut maps: Vec<_> = self
+                    .talk_maps
+                    .get(&script_id)
+                    .into_iter()
+                    .flatten()
+                    .copied()
+                    .collect();
+                maps.sort_unstable();
+                maps
+            }
+        }
+    }
+
+    fn is_walkable(&self, position: &Position) -> bool {
+        self.tile_maps
+            .get(&position.map_id)
+            .and_then(|m| m.tiles.get(position.y as usize))
+            .and_then(|r| r.get(position.x as usize))
+            .and_then(|id| self.tiles.get(id))
+            .map(|tile| tile.walkable)
+            .unwrap_or(false)
+    }
+}
+
+/// Whether a Select of the instructions has an option with the text
+fn has_option(scripts: &[ScriptKind], text: &str) -> bool {
+    scripts.iter().any(|s| {
+        if let ScriptKind::Select(options) = s {
+            if options.iter().any(|option| option.text == text) {
+                return true;
+            }
+        }
+        let mut b = 0;
+        while let Some(v) = s.branch(b) {
+            if has_option(v, text) {
+                return true;
+            }
+            b += 1;
+        }
+        false
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{testing, Game, Script};
+    use super::*;
+
+    /// Issues of script 1000 compiled from the source, besides Unreferenced
+    fn issues(game: &mut Game, source: &str) -> Vec<(Vec<usize>, ScriptIssueKind)> {
+        let script = Script::compile(1000, "test", source).unwrap();
+        game.novel_system.scripts.insert(1000, script);
+        analyze_scripts(
+            &game.novel_system,
+            &game.characters,
+            &game.character_templates,
+            &game.tile_maps,
+            &game.tiles,
+        )
+        .into_iter()
+        .filter(|i| i.script_id == 1000 && !matches!(i.kind, ScriptIssueKind::Unreferenced))
+        .map(|i| (i.path, i.kind))
+        .collect()
+    }
+
+    #[test]
+    fn after_jump() {
+        let mut game = testing::game();
+        let found = issues(&mut game, "@end\nLost\nLost too\n");
+        assert!(matches!(found.as_slice(), [(p, ScriptIssueKind::AfterJump)] if p == &[1]));
+        let found = issues(&mut game, "@goto a\nSkipped\n@label a\nShown\n");
+        assert!(matches!(found.as_slice(), [(p, ScriptIssueKind::AfterJump)] if p == &[1]));
+    }
+
+    #[test]
+    fn never_met() {
+        let mut game = testing::game();
+        let found = issues(&mut game, "@if Selected(1000, \"Maybe\")\n    Never\n");
+        assert!(matches!(found.as_slice(), [(p, ScriptIssueKind::NeverMet)] if p == &[0, 0]));
+        let source = "@if Not(Selected(1000, \"Maybe\"))\n    Always\n@else\n    Never\n";
+        let found = issues(&mut game, source);
+        assert!(matches!(found.as_slice(), [(p, ScriptIssueKind::NeverMet)] if p == &[0, 1]));
+        let source = "- Maybe\n    Then\n- No\n    Fine\n@if Selected(1000, \"Maybe\")\n    Met\n";
+        assert!(issues(&mut game, source).is_empty());
+    }
+
+    #[test]
+    fn empty_option() {
+        let mut game = testing::game();
+        let found = issues(&mut game, "- Yes\n    Fine\n- No\n");
+        assert!(matches!(
+            found.as_slice(),
+            [(p, ScriptIssueKind::EmptyOption(text))] if p == &[0, 1] && text == "No"
+        ));
+        assert!(issues(&mut game, "- Yes\n- No\nBye\n").is_empty());
+    }
+
+    #[test]
+    fn forget_skill_without_select_ability() {
+        let mut game = testing::game();
+        let found = issues(&mut game, "@forget_skill\n");
+        assert!(matches!(
+            found.as_slice(),
+            [(p, ScriptIssueKind::ForgetSkillWithoutSelectAbility)] if p == &[0]
+        ));
+        let source = "@select_ability\n    @forget_skill\n@forget_skill\n";
+        assert!(issues(&mut game, source).is_empty());
+    }
+
+    #[test]
+    fn not_walkable() {
+        let mut game = testing::game();
+        testing::add_map(&mut game, 99, &[".#"]);
+        game.characters.get_mut(&1).unwrap().position = Position {
+            map_id: 99,
+            x: 0,
+            y: 0,
+        };
+        let found = issues(&mut game, "@move 1 1 0\n");
+        assert!(matches!(
+            found.as_slice(),
+            [(p, ScriptIssueKind::NotWalkable(99, 1, 0))] if p == &[0]
+        ));
+        assert!(issues(&mut game, "@move 1 0 0\n").is_empty());
+    }
+
+    #[test]
+    fn unreferenced() {
+        let mut game = testing::game();
+        for (id, source) in &[(1000, "@call 1001\n"), (1001, "Called\n")] {
+            let script = Script::compile(*id, "test", source).unwrap();
+            game.novel_system.scripts.insert(*id, script);
+        }
+        let unreferenced: Vec<_> = analyze_scripts(
+            &game.novel_system,
+            &game.characters,
+            &game.character_templates,
+            &game.tile_maps,
+            &game.tiles,
+        )
+        .into_iter()
+        .filter(|i| matches!(i.kind, ScriptIssueKind::Unreferenced))
+        .map(|i| i.script_id)
+        .collect();
+        assert_eq!(unreferenced, vec![1000]);
+    }
+}
@@ -1,4 +1,4 @@
-use super::Game;
+use super::{Condition, Controller, Game, QuestEvent, QuestSystem, Value};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -8,12 +8,17 @@ pub struct Area {
     pub y: i32,
     pub w: i32,
     pub h: i32,
+    /// Events only fire when the entering character meets the condition
+    pub condition: Option<Condition>,
     pub events: Vec<Event>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum Event {
     StartBossFight,
+    SetVariable(String, Value),
+    AddVariable(String, i32),
+    ClearVariable(String),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,34 +27,47 @@ pub struct AreaSystem;
 impl AreaSystem {
     pub fn character_position_changed(game: &mut Game, character_id: i32) {
         if let Some(position) = game.characters.get(&character_id).map(|c| c.position) {
-            for (_, area) in game.areas.iter().filter(|(_, a)| {
-                a.map_id == position.map_id
-                    && a.x <= position.x
-                    && position.x < a.x + a.w
-                    && a.y <= position.y
-                    && position.y < a.y + a.h
-            }) {
-                for event in &area.events {
-                    match event {
-                        Event::StartBossFight => {
-                            // ....
-                            for (_, c) in
-                                game.characters
-                                    .iter_mut()
-                                    .filter(|(_, c)| match c.controller {
-                                        super::Controller::Boss { waiting: _ } => true,
-                                        _ => false,
-                                    })
-                            {
-                                match c.controller {
-                                    super::Controller::Boss { ref mut waiting } => *waiting = false,
-                                    _ => (),
-                                }
+            let areas: Vec<i32> = game
+                .areas
+                .iter()
+                .filter(|(_, a)| {
+                    a.map_id == position.map_id
+                        && a.x <= position.x
+                        && position.x < a.x + a.w
+                        && a.y <= position.y
+                        && position.y < a.y + a.h
+                })
+                .filter(|(_, a)| {
+                    a.condition
+                        .as_ref()
+                        .map(|c| Condition::check(game, c, character_id, character_id))
+                        .unwrap_or(true)
+                })
+                .map(|(id, _)| *id)
+                .collect();
+            let events: Vec<Event> = areas
+                .iter()
+                .filter_map(|id| game.areas.get(id))
+                .flat_map(|a| a.events.iter().cloned())
+                .collect();
+            for event in events {
+                match event {
+                    Event::StartBossFight => {
+                        // ....
+                        for (_, c) in game.characters.iter_mut() {
+                            if let Controller::Boss { ref mut waiting } = c.controller {
+                                *waiting = false;
                             }
                         }
                     }
+                    Event::SetVariable(name, value) => game.variable_system.set(&name, value),
+                    Event::AddVariable(name, i) => game.variable_system.add(&name, i),
+                    Event::ClearVariable(name) => game.variable_system.clear(&name),
                 }
             }
+            for area_id in areas {
+                QuestSystem::notify(game, QuestEvent::Reached(character_id, area_id));
+            }
         }
     }
 }
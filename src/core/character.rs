@@ -1,13 +1,24 @@
-use super::{command::CommandError, Attribute, Command, Game, Position, Target, TurnKind};
+use super::{
+    command::CommandError, Attribute, AttributeKind, Command, Condition, Game, GameEvent, Position,
+    QuestEvent, QuestSystem, Target, TurnKind,
+};
 use serde::{Deserialize, Serialize};
 
+/// Experience reaching each level, from level 1
+pub const LEVEL_EXPERIENCE: [i32; 5] = [0, 5, 15, 30, 50];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Character {
     pub id: i32,
+    pub name: String,
     pub crowd_controls: CharacterCrowdControl,
     pub position: Position,
     pub controller: Controller,
     pub items: Vec<CharacterItem>,
+    /// Equipped items by item id, at most one per slot. They are not in
+    /// `items` and their modifiers are in the attributes.
+    #[serde(default)]
+    pub equipment: Vec<i32>,
     pub character_action_ids: Vec<i32>,
     pub race: Race,
     pub character_sprite_id: i32,
@@ -24,12 +35,24 @@ pub struct Character {
     pub sanity: Attribute,
     pub hidden: bool,
     pub command_id: Option<i32>,
+    /// Script used when no dialogue matches
     pub talk_id: i32,
+    /// Checked in order when someone talks to the character
+    #[serde(default)]
+    pub dialogues: Vec<Dialogue>,
     pub shop: Option<Shop>,
     pub gold: i32,
     pub wood: i32,
 }
 
+/// Script to start when the condition is met, checked with the one talking as
+/// initiator and the character as target
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Dialogue {
+    pub condition: Condition,
+    pub talk_id: i32,
+}
+
 impl Character {
     pub fn move_command(
         game: &mut Game,
@@ -61,6 +84,50 @@ impl Character {
         }
     }
 
+    /// Script of the first matching dialogue of the target, else its talk_id
+    pub fn talk_id_for(game: &Game, character_id: i32, target_id: i32) -> Option<i32> {
+        let target = game.characters.get(&target_id)?;
+        let id = target
+            .dialogues
+            .iter()
+            .find(|d| Condition::check(game, &d.condition, character_id, target_id))
+            .map(|d| d.talk_id)
+            .unwrap_or(target.talk_id);
+        Some(id)
+    }
+
+    pub fn item_count(&self, item_id: i32) -> i32 {
+        self.items
+            .iter()
+            .filter(|ci| ci.item_id == item_id)
+            .map(|ci| ci.quantity)
+            .sum()
+    }
+
+    /// Add to the stack of the item, or start a new one
+    pub fn give_item(&mut self, item_id: i32, quantity: i32) {
+        match self.items.iter_mut().find(|ci| ci.item_id == item_id) {
+            Some(ci) => ci.quantity += quantity,
+            None => self.items.push(CharacterItem { item_id, quantity }),
+        }
+    }
+
+    /// Remove up to `quantity` of the item, returns how many were removed
+    pub fn take_item(&mut self, item_id: i32, quantity: i32) -> i32 {
+        let mut left = quantity;
+        for ci in self.items.iter_mut().filter(|ci| ci.item_id == item_id) {
+            let n = ci.quantity.min(left);
+            ci.quantity -= n;
+            left -= n;
+        }
+        self.items.retain(|ci| ci.quantity > 0);
+        quantity - left
+    }
+
+    pub fn heal(&mut self, amount: i32) {
+        self.hp.damage = (self.hp.damage - amount).max(0);
+    }
+
     pub fn gain_exp(&mut self, exp: i32) {
         self.set_experience(self.experience + exp);
     }
@@ -68,13 +135,11 @@ impl Character {
         self.experience = exp;
         // exp = 100 * level ^ 1.5
         // let level = ((self.experience as f32 / 100.0).ln() / 1.5).exp().floor() as i32;
-        let level = match self.experience {
-            i32::MIN..=4 => 1,
-            5..=14 => 2,
-            15..=29 => 3,
-            30..=49 => 4,
-            50..=i32::MAX => 5,
-        };
+        let level = LEVEL_EXPERIENCE
+            .iter()
+            .take_while(|exp| self.experience >= **exp)
+            .count()
+            .max(1) as i32;
         if level != self.level {
             self.level = level;
             self.hp.base = 100 + level * 10;
@@ -82,12 +147,60 @@ impl Character {
         }
     }
 
+    /// Experience needed for the next level, None at the last level
+    pub fn next_level_experience(&self) -> Option<i32> {
+        LEVEL_EXPERIENCE.get(self.level as usize).copied()
+    }
+
+    pub fn attribute(&self, kind: AttributeKind) -> &Attribute {
+        match kind {
+            AttributeKind::Hp => &self.hp,
+            AttributeKind::Mp => &self.mp,
+            AttributeKind::Attack => &self.attack,
+            AttributeKind::Defence => &self.defence,
+            AttributeKind::Sanity => &self.sanity,
+        }
+    }
+
+    pub fn attribute_mut(&mut self, kind: AttributeKind) -> &mut Attribute {
+        match kind {
+            AttributeKind::Hp => &mut self.hp,
+            AttributeKind::Mp => &mut self.mp,
+            AttributeKind::Attack => &mut self.attack,
+            AttributeKind::Defence => &mut self.defence,
+            AttributeKind::Sanity => &mut self.sanity,
+        }
+    }
+
     pub fn kill_experience(&self) -> i32 {
         // (100.0 * (1.05 as f32).powi(self.level)) as i32
         1
     }
 
-    pub fn take_damage(game: &mut Game, damage: i32, target_id: i32, source_id: i32) {
+    /// Apply the hazard of the tile the character is standing on
+    pub fn enter_tile(game: &mut Game, character_id: i32) {
+        let hazard = game
+            .characters
+            .get(&character_id)
+            .and_then(|c| game.tile_at_position(&c.position))
+            .and_then(|t| t.hazard.as_ref())
+            .map(|h| (h.damage, h.crowd_control));
+        if let Some((damage, crowd_control)) = hazard {
+            if let Some((kind, duration)) = crowd_control {
+                Character::apply_status(game, kind, duration, character_id, None);
+            }
+            if damage != 0 {
+                Character::take_damage(game, damage, character_id, None);
+            }
+        }
+    }
+
+    pub fn take_damage(game: &mut Game, damage: i32, target_id: i32, source_id: Option<i32>) {
+        game.events.push(GameEvent::Damaged {
+            target_id,
+            source_id,
+            amount: damage,
+        });
         let target = game.characters.get_mut(&target_id).unwrap();
         target.hp.damage += damage;
         if !target.dead && target.hp.current() <= -1000 {
@@ -95,19 +208,106 @@ impl Character {
             target.hidden = true;
         }
         if !target.defeated && target.hp.current() <= 0 {
-            target.defeated = true;
-            let exp = target.kill_experience();
-            target.hidden = true;
+            Character::defeat(game, target_id, source_id);
+        }
+    }
 
-            let character = game.characters.get_mut(&source_id).unwrap();
-            character.gain_exp(exp);
+    /// Restore hp up to the maximum, from `source_id` if any
+    pub fn heal_hp(game: &mut Game, amount: i32, target_id: i32, source_id: Option<i32>) {
+        if let Some(c) = game.characters.get_mut(&target_id) {
+            let damage = c.hp.damage;
+            c.heal(amount);
+            let amount = damage - c.hp.damage;
+            game.events.push(GameEvent::Healed {
+                target_id,
+                source_id,
+                amount,
+            });
+        }
+    }
+
+    /// Restore hp, from an action of `source_id` if any
+    pub fn recover_hp(game: &mut Game, amount: i32, target_id: i32, source_id: Option<i32>) {
+        if let Some(c) = game.characters.get_mut(&target_id) {
+            c.hp.damage -= amount;
+            game.events.push(GameEvent::Healed {
+                target_id,
+                source_id,
+                amount,
+            });
+        }
+    }
 
-            game.turn_system.turn_queue.retain(|t| match &t.kind {
-                TurnKind::Character { character_id } => *character_id != target_id,
-                TurnKind::Respawn { character_id: _ } => true,
+    /// Apply the crowd control, from an action of `source_id` if any
+    pub fn apply_status(
+        game: &mut Game,
+        kind: CrowdControlKind,
+        duration: i32,
+        target_id: i32,
+        source_id: Option<i32>,
+    ) {
+        if let Some(c) = game.characters.get_mut(&target_id) {
+            c.crowd_controls.apply(kind, duration);
+            game.events.push(GameEvent::StatusApplied {
+                target_id,
+                source_id,
+                kind,
+                duration,
             });
         }
     }
+
+    /// Gain or lose experience, noting level ups
+    pub fn give_exp(game: &mut Game, character_id: i32, exp: i32) {
+        if let Some(c) = game.characters.get_mut(&character_id) {
+            let level = c.level;
+            c.gain_exp(exp);
+            if c.level > level {
+                let level = c.level;
+                game.events.push(GameEvent::LevelUp {
+                    character_id,
+                    level,
+                });
+            }
+        }
+    }
+
+    /// Add the items to the character, noting it
+    pub fn gain_item(game: &mut Game, character_id: i32, item_id: i32, quantity: i32) {
+        if let Some(c) = game.characters.get_mut(&character_id) {
+            c.give_item(item_id, quantity);
+            game.events.push(GameEvent::ItemGained {
+                character_id,
+                item_id,
+                quantity,
+            });
+        }
+    }
+
+    /// Take the character out of the fight, `source_id` gains the kill experience
+    pub fn defeat(game: &mut Game, target_id: i32, source_id: Option<i32>) {
+        let target = game.characters.get_mut(&target_id).unwrap();
+        if target.defeated {
+            return;
+        }
+        target.defeated = true;
+        let exp = target.kill_experience();
+        target.hidden = true;
+        game.events.push(GameEvent::Defeated {
+            target_id,
+            source_id,
+        });
+
+        if let Some(source_id) = source_id {
+            Character::give_exp(game, source_id, exp);
+        }
+
+        game.turn_system.turn_queue.retain(|t| match &t.kind {
+            TurnKind::Character { character_id } => *character_id != target_id,
+            TurnKind::Respawn { character_id: _ } => true,
+        });
+        QuestSystem::notify(game, QuestEvent::Defeated(target_id, source_id));
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Copy, Clone)]
@@ -118,7 +318,21 @@ pub enum Race {
     Demon,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Race {
+    pub const ALL: [Race; 4] = [Race::Human, Race::Elf, Race::Orc, Race::Demon];
+
+    /// Localization key of the name
+    pub fn key(&self) -> &'static str {
+        match self {
+            Race::Human => "race.human",
+            Race::Elf => "race.elf",
+            Race::Orc => "race.orc",
+            Race::Demon => "race.demon",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Copy, Clone)]
 pub enum Controller {
     Player,
     Enemy,
@@ -148,9 +362,24 @@ impl CharacterCrowdControl {
         self.root = (self.root - 1).max(0);
         self.silent = (self.silent - 1).max(0);
     }
+
+    pub fn apply(&mut self, kind: CrowdControlKind, duration: i32) {
+        match kind {
+            CrowdControlKind::Stun => self.stun = self.stun.max(duration),
+            CrowdControlKind::Charm => self.charm = self.charm.max(duration),
+            CrowdControlKind::Shock => {
+                self.shock = self.shock.max(duration);
+                self.shocked = false;
+            }
+            CrowdControlKind::Poison => self.poison = self.poison.max(duration),
+            CrowdControlKind::Sleep => self.sleep = self.sleep.max(duration),
+            CrowdControlKind::Root => self.root = self.root.max(duration),
+            CrowdControlKind::Silent => self.silent = self.silent.max(duration),
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Copy, Clone)]
 pub enum CrowdControlKind {
     // Cannot do anything
     Stun,
@@ -166,6 +395,31 @@ pub enum CrowdControlKind {
     Silent,
 }
 
+/// Data to spawn a character from, in character_templates.ron
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CharacterTemplate {
+    pub id: i32,
+    pub name: String,
+    pub controller: Controller,
+    pub race: Race,
+    pub character_sprite_id: i32,
+    /// Actions the character knows, by action id
+    pub action_ids: Vec<i32>,
+    pub items: Vec<CharacterItem>,
+    pub level: i32,
+    pub experience: i32,
+    pub hp: i32,
+    pub mp: i32,
+    pub attack: i32,
+    pub defence: i32,
+    pub sanity: i32,
+    pub talk_id: i32,
+    #[serde(default)]
+    pub dialogues: Vec<Dialogue>,
+    pub gold: i32,
+    pub wood: i32,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CharacterAction {
     pub action_id: i32,
@@ -182,7 +436,7 @@ impl CharacterAction {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CharacterItem {
     pub item_id: i32,
     pub quantity: i32,
@@ -198,8 +452,19 @@ pub struct Item {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ItemKind {
-    Potion,
+    /// Restores hp and mp
+    Potion { hp: i32, mp: i32 },
+    /// ActionBook(action_id), teaches the action
     ActionBook(i32),
+    /// Adds the modifiers to the attributes while equipped
+    Equipment(EquipmentSlot, Vec<(AttributeKind, i32)>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Copy, Clone)]
+pub enum EquipmentSlot {
+    Weapon,
+    Armor,
+    Accessory,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
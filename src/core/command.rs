@@ -1,9 +1,14 @@
-use super::{ActionKind, AnimationEffect, Character, EffectKind, Game, Position, Target};
+use super::{
+    ActionKind, AnimationEffect, Character, CrowdControlKind, EffectKind, Game, GameEvent,
+    Position, Target,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum CommandError {
     Target,
+    /// Target is out of sight or behind an obstacle
+    Blocked,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -40,6 +45,26 @@ impl Command {
     ) -> Result<Self, CommandError> {
         let action = game.actions.get(&action_id).unwrap();
         let character = game.characters.get(&character_id).unwrap();
+        match action.action_kind {
+            ActionKind::Idle | ActionKind::Move | ActionKind::Talk => {}
+            _ => {
+                // Only what targets someone or somewhere else can be blocked
+                let position = match target {
+                    Target::None => None,
+                    Target::Character(id) if id == character_id => None,
+                    Target::Character(id) => game.characters.get(&id).map(|c| c.position),
+                    Target::Position(p) if p == character.position => None,
+                    Target::Position(p) => Some(p),
+                };
+                if let Some(position) = position {
+                    if !game.line_of_sight(&character.position, &position)
+                        || !game.line_of_fire(&character.position, &position)
+                    {
+                        return Err(CommandError::Blocked);
+                    }
+                }
+            }
+        }
         let data = match &action.action_kind {
             ActionKind::Idle => CommandData::None,
             ActionKind::Talk => CommandData::None,
@@ -158,41 +183,40 @@ impl Command {
                         Target::Position(_) => panic!(),
                     };
                     let character_id = command.character_id;
-                    let id = game.characters.get(&target_id).map(|c| c.talk_id).unwrap();
+                    let id = Character::talk_id_for(game, character_id, target_id).unwrap();
                     game.start_novel(id, character_id, target_id);
                 }
                 ActionKind::Move => match command.data {
                     CommandData::Move { from, to } => {
                         let character_id = command.character_id;
-                        let is_walkable = game
-                            .tile_maps
-                            .get(&to.map_id)
-                            .map(|m| &m.tiles)
-                            .and_then(|tiles| tiles.get(to.y as usize))
-                            .and_then(|r| r.get(to.x as usize))
-                            .and_then(|id| game.tiles.get(id))
-                            .map(|t| t.walkable)
-                            .unwrap_or(false);
+                        let can_step = game.can_step(&from, &to);
                         let is_collide = game.character_at_position(&to).is_some();
-                        let pos = if !is_walkable || is_collide { from } else { to };
+                        let pos = if !can_step || is_collide { from } else { to };
                         let c = game.characters.get_mut(&character_id).unwrap();
                         c.offset_x = 0.0;
                         c.offset_y = 0.0;
                         game.character_set_position(character_id, pos);
+                        if pos != from {
+                            Character::enter_tile(game, character_id);
+                        }
                     }
                     _ => panic!(),
                 },
                 ActionKind::Damage(damage) => {
                     let damage = *damage;
                     let character_id = command.character_id;
+                    let target = command.target;
                     let characters = EffectKind::effective_characters(
                         game,
                         character_id,
-                        &command.target,
+                        &target,
                         action.effect_kind,
                     );
+                    if characters.is_empty() {
+                        Command::miss(game, character_id, target);
+                    }
                     for target_id in characters {
-                        Character::take_damage(game, damage, target_id, character_id);
+                        Character::take_damage(game, damage, target_id, Some(character_id));
                     }
                 }
                 ActionKind::Drain => {
@@ -206,22 +230,31 @@ impl Command {
                     }
 
                     let character_id = command.character_id;
+                    let target = command.target;
                     let characters = EffectKind::effective_characters(
                         game,
                         character_id,
-                        &command.target,
+                        &target,
                         action.effect_kind,
                     );
+                    if characters.is_empty() {
+                        Command::miss(game, character_id, target);
+                    }
                     for target_id in characters {
-                        let target = game.characters.get_mut(&target_id).unwrap();
+                        let target = game.characters.get(&target_id).unwrap();
                         if target.experience > 0 {
-                            target.gain_exp(-1);
-                            let character = game.characters.get_mut(&character_id).unwrap();
-                            character.gain_exp(1);
+                            Character::give_exp(game, target_id, -1);
+                            Character::give_exp(game, character_id, 1);
+                            game.events.push(GameEvent::ExperienceDrained {
+                                target_id,
+                                source_id: character_id,
+                                amount: 1,
+                            });
                         }
                     }
                 }
                 ActionKind::Lullaby { duration } => {
+                    let duration = *duration;
                     let character_id = command.character_id;
                     let characters = EffectKind::effective_characters(
                         game,
@@ -230,11 +263,17 @@ impl Command {
                         action.effect_kind,
                     );
                     for target_id in characters {
-                        let target = game.characters.get_mut(&target_id).unwrap();
-                        target.crowd_controls.sleep = target.crowd_controls.sleep.max(*duration);
+                        Character::apply_status(
+                            game,
+                            CrowdControlKind::Sleep,
+                            duration,
+                            target_id,
+                            Some(character_id),
+                        );
                     }
                 }
                 ActionKind::Thunder { duration } => {
+                    let duration = *duration;
                     let character_id = command.character_id;
                     let characters = EffectKind::effective_characters(
                         game,
@@ -243,12 +282,17 @@ impl Command {
                         action.effect_kind,
                     );
                     for target_id in characters {
-                        let target = game.characters.get_mut(&target_id).unwrap();
-                        target.crowd_controls.shock = target.crowd_controls.shock.max(*duration);
-                        target.crowd_controls.shocked = false;
+                        Character::apply_status(
+                            game,
+                            CrowdControlKind::Shock,
+                            duration,
+                            target_id,
+                            Some(character_id),
+                        );
                     }
                 }
                 ActionKind::Root(duration) => {
+                    let duration = *duration;
                     let character_id = command.character_id;
                     let characters = EffectKind::effective_characters(
                         game,
@@ -257,11 +301,17 @@ impl Command {
                         action.effect_kind,
                     );
                     for target_id in characters {
-                        let target = game.characters.get_mut(&target_id).unwrap();
-                        target.crowd_controls.root = target.crowd_controls.root.max(*duration);
+                        Character::apply_status(
+                            game,
+                            CrowdControlKind::Root,
+                            duration,
+                            target_id,
+                            Some(character_id),
+                        );
                     }
                 }
                 ActionKind::HpRecover(i) => {
+                    let i = *i;
                     let character_id = command.character_id;
                     let characters = EffectKind::effective_characters(
                         game,
@@ -270,8 +320,7 @@ impl Command {
                         action.effect_kind,
                     );
                     for target_id in characters {
-                        let target = game.characters.get_mut(&target_id).unwrap();
-                        target.hp.damage -= *i;
+                        Character::recover_hp(game, i, target_id, Some(character_id));
                     }
                 }
                 ActionKind::MpRecover(i) => {
@@ -291,6 +340,71 @@ impl Command {
         }
     }
 
+    /// Note an attack on a tile that hit no one
+    fn miss(game: &mut Game, character_id: i32, target: Target) {
+        if let Target::Position(position) = target {
+            game.events.push(GameEvent::Missed {
+                source_id: character_id,
+                position,
+            });
+        }
+    }
+
+    /// Turn time the command costs its character
+    pub fn turn_time(game: &Game, command_id: i32) -> i32 {
+        let command = game.commands.get(&command_id).unwrap();
+        let moved = match command.data {
+            CommandData::Move { from, to } => game
+                .characters
+                .get(&command.character_id)
+                .map(|c| c.position == to && to != from)
+                .unwrap_or(false),
+            _ => false,
+        };
+        Command::cost(game, command.action_id, &command.data, moved)
+    }
+
+    /// Turn time the action would cost the character on the target, a Move
+    /// costs its destination tile if the character can step there
+    pub fn preview_turn_time(
+        game: &Game,
+        character_id: i32,
+        action_id: i32,
+        target: &Target,
+    ) -> i32 {
+        let action_kind = game.actions.get(&action_id).map(|a| &a.action_kind);
+        let character = game.characters.get(&character_id);
+        let data = match (action_kind, target, character) {
+            (Some(ActionKind::Move), Target::Position(to), Some(c)) => CommandData::Move {
+                from: c.position,
+                to: *to,
+            },
+            _ => CommandData::None,
+        };
+        let moved = match data {
+            CommandData::Move { from, to } => {
+                to != from && game.can_step(&from, &to) && game.character_at_position(&to).is_none()
+            }
+            _ => false,
+        };
+        Command::cost(game, action_id, &data, moved)
+    }
+
+    /// Turn time of the action, `moved` whether a Move reached its destination
+    fn cost(game: &Game, action_id: i32, data: &CommandData, moved: bool) -> i32 {
+        match data {
+            CommandData::Move { to, .. } if moved => {
+                game.tile_at_position(to).map(|t| t.cost).unwrap_or(1)
+            }
+            CommandData::Move { .. } => 1,
+            _ => game
+                .actions
+                .get(&action_id)
+                .map(|a| a.turn_time)
+                .unwrap_or(1),
+        }
+    }
+
     pub fn update(game: &mut Game, command_id: i32) {
         let command = game.commands.get(&command_id).unwrap();
         let duration = { game.actions.get(&command.action_id).unwrap().duration };
@@ -325,3 +439,121 @@ impl Command {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing;
+    use super::*;
+
+    fn game() -> Game {
+        let mut game = testing::game();
+        testing::add_map(&mut game, 99, &["..........", "..........", ".........."]);
+        game.characters.get_mut(&2).unwrap().position = Position {
+            map_id: 99,
+            x: 1,
+            y: 1,
+        };
+        game.characters.get_mut(&3).unwrap().position = Position {
+            map_id: 99,
+            x: 2,
+            y: 1,
+        };
+        game
+    }
+
+    /// Run the action of character 2 to the end, returning the events
+    fn run(game: &mut Game, action: &str, target: Target) -> Vec<GameEvent> {
+        let action_id = game.actions.values().find(|a| a.name == action).unwrap().id;
+        let command = Command::new(game, 2, action_id, target).unwrap();
+        game.commands.insert(99, command);
+        let seen = game.events.count;
+        Command::start(game, 99);
+        Command::end(game, 99);
+        game.events.since(seen).cloned().collect()
+    }
+
+    #[test]
+    fn damaged() {
+        let mut game = game();
+        let events = run(&mut game, "Melee", Target::Character(3));
+        assert_eq!(
+            events,
+            vec![GameEvent::Damaged {
+                target_id: 3,
+                source_id: Some(2),
+                amount: 10,
+            }]
+        );
+    }
+
+    #[test]
+    fn missed() {
+        let mut game = game();
+        let position = Position {
+            map_id: 99,
+            x: 8,
+            y: 1,
+        };
+        let events = run(&mut game, "Explosion", Target::Position(position));
+        assert_eq!(
+            events,
+            vec![GameEvent::Missed {
+                source_id: 2,
+                position,
+            }]
+        );
+    }
+
+    #[test]
+    fn defeated() {
+        let mut game = game();
+        let hp = game.characters[&3].hp.current();
+        game.characters.get_mut(&3).unwrap().hp.damage += hp - 5;
+        let events = run(&mut game, "Melee", Target::Character(3));
+        assert_eq!(
+            events[..2],
+            [
+                GameEvent::Damaged {
+                    target_id: 3,
+                    source_id: Some(2),
+                    amount: 10,
+                },
+                GameEvent::Defeated {
+                    target_id: 3,
+                    source_id: Some(2),
+                },
+            ]
+        );
+        assert!(game.characters[&3].defeated);
+    }
+
+    #[test]
+    fn preview_turn_time_as_run() {
+        let mut game = game();
+        // Poison swamp, costs 2
+        game.tile_maps.get_mut(&99).unwrap().tiles[1][0] = 11;
+        let swamp = Position {
+            map_id: 99,
+            x: 0,
+            y: 1,
+        };
+        let move_id = game.actions.values().find(|a| a.name == "Move").unwrap().id;
+        let melee = game.actions.values().find(|a| a.name == "Melee").unwrap();
+        let (melee_id, melee_time) = (melee.id, melee.turn_time);
+
+        assert_eq!(
+            Command::preview_turn_time(&game, 2, move_id, &Target::Position(swamp)),
+            2
+        );
+        run(&mut game, "Move", Target::Position(swamp));
+        assert_eq!(Command::turn_time(&game, 99), 2);
+
+        // Blocked by character 3
+        let occupied = game.characters[&3].position;
+        let target = Target::Position(occupied);
+        assert_eq!(Command::preview_turn_time(&game, 2, move_id, &target), 1);
+        let target = Target::Character(3);
+        let preview = Command::preview_turn_time(&game, 2, melee_id, &target);
+        assert_eq!(preview, melee_time);
+    }
+}
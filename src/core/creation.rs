@@ -0,0 +1,126 @@
+use super::{CharacterItem, Game, Position, Race};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Where new player characters start
+pub const PLAYER_START: Position = Position {
+    map_id: 0,
+    x: 5,
+    y: 8,
+};
+pub const MAX_NAME_LEN: usize = 16;
+
+/// Starting class picked when creating a player character, see
+/// player_classes.ron. Name and description are localization keys.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlayerClass {
+    pub id: i32,
+    pub name: String,
+    pub description: String,
+    /// Actions the character knows, by action id
+    pub action_ids: Vec<i32>,
+    pub items: Vec<CharacterItem>,
+    pub level: i32,
+    pub experience: i32,
+    pub hp: i32,
+    pub mp: i32,
+    pub attack: i32,
+    pub defence: i32,
+    pub sanity: i32,
+    /// Attribute points the player distributes
+    pub points: i32,
+    pub gold: i32,
+    pub wood: i32,
+}
+
+/// Points added to the base attributes of the class, one point for one base
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy)]
+pub struct AttributePoints {
+    pub hp: i32,
+    pub mp: i32,
+    pub attack: i32,
+    pub defence: i32,
+    pub sanity: i32,
+}
+
+impl AttributePoints {
+    pub fn total(&self) -> i32 {
+        self.hp + self.mp + self.attack + self.defence + self.sanity
+    }
+
+    fn negative(&self) -> bool {
+        [self.hp, self.mp, self.attack, self.defence, self.sanity]
+            .iter()
+            .any(|p| *p < 0)
+    }
+}
+
+/// Choices made when creating a player character
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CharacterCreation {
+    /// Shown as is, not a localization key
+    pub name: String,
+    pub race: Race,
+    pub class_id: i32,
+    pub character_sprite_id: i32,
+    pub points: AttributePoints,
+}
+
+#[derive(Debug)]
+pub enum CreationError {
+    EmptyName,
+    NameTooLong,
+    NoClass(i32),
+    NoCharacterSprite(i32),
+    NegativePoints,
+    /// TooManyPoints(spent, available)
+    TooManyPoints(i32, i32),
+}
+
+impl fmt::Display for CreationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CreationError::EmptyName => write!(f, "name is empty"),
+            CreationError::NameTooLong => write!(f, "name is over {} chars", MAX_NAME_LEN),
+            CreationError::NoClass(id) => write!(f, "no class {}", id),
+            CreationError::NoCharacterSprite(id) => write!(f, "no character sprite {}", id),
+            CreationError::NegativePoints => write!(f, "negative attribute points"),
+            CreationError::TooManyPoints(spent, available) => {
+                write!(f, "{} points spent out of {}", spent, available)
+            }
+        }
+    }
+}
+
+impl CharacterCreation {
+    /// Check the choices against the content of the game
+    pub fn validate<'a>(&self, game: &'a Game) -> Result<&'a PlayerClass, CreationError> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(CreationError::EmptyName);
+        }
+        if name.chars().count() > MAX_NAME_LEN {
+            return Err(CreationError::NameTooLong);
+        }
+        let class = game
+            .player_classes
+            .get(&self.class_id)
+            .ok_or(CreationError::NoClass(self.class_id))?;
+        if !game
+            .character_sprites
+            .contains_key(&self.character_sprite_id)
+        {
+            return Err(CreationError::NoCharacterSprite(self.character_sprite_id));
+        }
+        if self.points.negative() {
+            return Err(CreationError::NegativePoints);
+        }
+        if self.points.total() > class.points {
+            return Err(CreationError::TooManyPoints(
+                self.points.total(),
+                class.points,
+            ));
+        }
+        Ok(class)
+    }
+}
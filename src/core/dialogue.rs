@@ -0,0 +1,695 @@
+//! Plain text format for novel scripts.
+//!
+//! ```text
+//! # Comment
+//! [target] Welcome, {initiator.name}
+//! @if FlagSet("met_guide")
+//!     Welcome back
+//! @else
+//!     Nice to meet you
+//!     @set met_guide Bool(true)
+//! @label menu
+//! - Tell me something
+//!     Here is the tower
+//!     @goto menu
+//! - Leave
+//!     @end
+//! ```
+//!
+//! Every line is a text line unless it starts with `@` (command), `- ` (option
+//! of a select) or `#` (comment), start a line with `\` to write such text.
+//! A line starting with `[who]` is said by `who`, which is a character as in
+//! commands or any other name, optionally with a portrait: `[Voice|path]`.
+//! Blocks are the lines indented below `@if`, `@else`, `@select_ability` and
+//! options, consecutive options form one select, `@select` before an option
+//! starts a new one. An option block may start with `@visible condition` and
+//! `@enabled condition | reason`.
+//!
+//! Commands:
+//! `@background none|color r g b a|image path`, `@if condition`, `@else`,
+//! `@select`, `@select_ability`, `@restart`, `@shop`, `@surrender`, `@end`,
+//! `@move who x y`, `@position who x y map_id`, `@forget_skill`,
+//! `@practice_skill`, `@set name value`, `@add name amount`, `@clear name`,
+//! `@label name`, `@goto name`, `@call script_id`,
+//! `@give who item_id quantity`, `@take who item_id quantity`,
+//! `@gold who amount`, `@wood who amount`, `@heal who amount`,
+//! `@damage who amount`, `@status who kind duration`, `@exp who amount`,
+//! `@controller who controller`, `@spawn template_id x y map_id`,
+//! `@focus who seconds`, `@pan x y map_id seconds`, `@camera_reset seconds`,
+//! `@shake intensity seconds`, `@fade r g b a seconds`, `@wait seconds`,
+//! `@animation x y map_id seconds animation`, `@quest_start quest_id`,
+//! `@quest_advance quest_id`, `@quest_complete quest_id`.
+//! `who` is `initiator`, `target` or a character id, conditions, values,
+//! status kinds, controllers and animations are written in RON.
+
+use super::{
+    Background, Condition, NovelSystem, Position, Script, ScriptCharacter, ScriptKind,
+    SelectOption, Speaker, Value,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, path::Path};
+
+const INDENT: &str = "    ";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ParseError {
+    pub file: String,
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.file, self.line, self.message)
+    }
+}
+
+struct Line<'a> {
+    number: usize,
+    indent: usize,
+    content: &'a str,
+}
+
+struct Parser<'a> {
+    file: &'a str,
+    lines: Vec<Line<'a>>,
+    pos: usize,
+}
+
+impl Script {
+    pub fn compile(id: i32, file: &str, source: &str) -> Result<Script, ParseError> {
+        let mut lines = Vec::new();
+        for (i, line) in source.lines().enumerate() {
+            let content = line.trim_start();
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+            let indent = &line[..line.len() - content.len()];
+            if indent.contains('\t') {
+                return Err(ParseError {
+                    file: String::from(file),
+                    line: i + 1,
+                    message: String::from("indent with spaces, not tabs"),
+                });
+            }
+            lines.push(Line {
+                number: i + 1,
+                indent: indent.len(),
+                content: content.trim_end(),
+            });
+        }
+        let mut parser = Parser {
+            file,
+            lines,
+            pos: 0,
+        };
+        let scripts = parser.block(0)?;
+        if let Some(line) = parser.lines.get(parser.pos) {
+            return Err(parser.error(line.number, "unexpected indentation"));
+        }
+        Ok(Script { id, scripts })
+    }
+
+    pub fn export(&self) -> String {
+        let mut s = String::new();
+        export_block(&mut s, &self.scripts, 0);
+        s
+    }
+}
+
+impl NovelSystem {
+    /// Compile the files among `paths` named `<id>.script`, read with `read`,
+    /// and return the errors of the ones not loaded
+    pub fn load_scripts<P, R>(
+        &mut self,
+        paths: impl IntoIterator<Item = P>,
+        mut read: R,
+    ) -> Vec<String>
+    where
+        P: AsRef<Path>,
+        R: FnMut(&Path) -> Result<String, String>,
+    {
+        let mut errors = Vec::new();
+        for path in paths {
+            let path = path.as_ref();
+            if path.extension().and_then(|e| e.to_str()) != Some("script") {
+                continue;
+            }
+            let id = match path.file_stem().and_then(|s| s.to_str()?.parse().ok()) {
+                Some(id) => id,
+                None => {
+                    errors.push(format!("Script file name is not an id: {}", path.display()));
+                    continue;
+                }
+            };
+            let source = match read(path) {
+                Ok(source) => source,
+                Err(e) => {
+                    errors.push(format!("Cannot read {}: {}", path.display(), e));
+                    continue;
+                }
+            };
+            match Script::compile(id, &path.to_string_lossy(), &source) {
+                Ok(script) => {
+                    self.scripts.insert(id, script);
+                }
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+        errors
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, line: usize, message: &str) -> ParseError {
+        ParseError {
+            file: String::from(self.file),
+            line,
+            message: String::from(message),
+        }
+    }
+
+    /// Lines at exactly `indent`
+    fn block(&mut self, indent: usize) -> Result<Vec<ScriptKind>, ParseError> {
+        let mut scripts = Vec::new();
+        while let Some(line) = self.lines.get(self.pos) {
+            if line.indent < indent {
+                break;
+            }
+            if line.indent > indent {
+                return Err(self.error(line.number, "unexpected indentation"));
+            }
+            scripts.push(self.statement(indent)?);
+        }
+        Ok(scripts)
+    }
+
+    /// The block indented below the previous line, if any
+    fn children(&mut self, indent: usize) -> Result<Vec<ScriptKind>, ParseError> {
+        match self.lines.get(self.pos) {
+            Some(line) if line.indent > indent => {
+                let indent = line.indent;
+                self.block(indent)
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    fn statement(&mut self, indent: usize) -> Result<ScriptKind, ParseError> {
+        let number = self.lines[self.pos].number;
+        let content = self.lines[self.pos].content;
+        if is_option(content) {
+            let mut options = Vec::new();
+            while let Some(line) = self.lines.get(self.pos) {
+                if line.indent != indent || !is_option(line.content) {
+                    break;
+                }
+                let text = unescape(line.content[1..].trim_start());
+                self.pos += 1;
+                let mut option = SelectOption {
+                    text,
+                    visible: None,
+                    enabled: None,
+                    scripts: Vec::new(),
+                };
+                while let Some(line) = self.lines.get(self.pos) {
+                    if line.indent <= indent {
+                        break;
+                    }
+                    if let Some(rest) = line.content.strip_prefix("@visible ") {
+                        option.visible = Some(self.ron::<Condition>(line.number, rest.trim())?);
+                    } else if let Some(rest) = line.content.strip_prefix("@enabled ") {
+                        let (condition, reason) = match rest.rfind(" | ") {
+                            Some(i) => (&rest[..i], rest[i + 3..].trim()),
+                            None => (rest, ""),
+                        };
+                        let condition = self.ron::<Condition>(line.number, condition.trim())?;
+                        option.enabled = Some((condition, String::from(reason)));
+                    } else {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                option.scripts = self.children(indent)?;
+                options.push(option);
+            }
+            return Ok(ScriptKind::Select(options));
+        }
+        self.pos += 1;
+        if let Some(text) = content.strip_prefix('\\') {
+            return Ok(ScriptKind::Text(unescape(text)));
+        }
+        if let Some(rest) = content.strip_prefix('[') {
+            if let Some(end) = rest.find(']') {
+                let speaker = speaker(&rest[..end]);
+                return Ok(ScriptKind::Say(
+                    speaker,
+                    unescape(rest[end + 1..].trim_start()),
+                ));
+            }
+        }
+        let command = match content.strip_prefix('@') {
+            Some(command) => command,
+            None => return Ok(ScriptKind::Text(unescape(content))),
+        };
+        let (name, rest) = match command.find(' ') {
+            Some(i) => (&command[..i], command[i + 1..].trim()),
+            None => (command, ""),
+        };
+        let args: Vec<&str> = rest.split_whitespace().collect();
+        let s = match (name, &args[..]) {
+            ("if", _) => {
+                let condition = self.ron::<Condition>(number, rest)?;
+                let scripts = self.children(indent)?;
+                let is_else = self
+                    .lines
+                    .get(self.pos)
+                    .map(|line| line.indent == indent && line.content == "@else")
+                    .unwrap_or(false);
+                if is_else {
+                    self.pos += 1;
+                    let else_scripts = self.children(indent)?;
+                    ScriptKind::IfElse(condition, scripts, else_scripts)
+                } else {
+                    ScriptKind::If(condition, scripts)
+                }
+            }
+            ("else", _) => return Err(self.error(number, "@else without @if")),
+            ("visible", _) | ("enabled", _) => {
+                return Err(self.error(number, "only allowed at the start of an option"))
+            }
+            ("select", []) => match self.lines.get(self.pos) {
+                Some(line) if line.indent == indent && is_option(line.content) => {
+                    return self.statement(indent)
+                }
+                _ => return Err(self.error(number, "@select without options")),
+            },
+            ("select_ability", []) => ScriptKind::SelectAbility(self.children(indent)?),
+            ("background", ["none"]) => ScriptKind::Background(Background::None),
+            ("background", ["color", r, g, b, a]) => ScriptKind::Background(Background::Color(
+                self.number(number, r)?,
+                self.number(number, g)?,
+                self.number(number, b)?,
+                self.number(number, a)?,
+            )),
+            ("background", ["image", ..]) => {
+                let path = rest["image".len()..].trim();
+                ScriptKind::Background(Background::Image(String::from(path)))
+            }
+            ("restart", []) => ScriptKind::Restart,
+            ("shop", []) => ScriptKind::Shop,
+            ("surrender", []) => ScriptKind::Surrender,
+            ("end", []) => ScriptKind::End,
+            ("forget_skill", []) => ScriptKind::ForgetSkill,
+            ("practice_skill", []) => ScriptKind::PracticeSkill,
+            ("move", [who, x, y]) => ScriptKind::CharacterMove(
+                self.character(number, who)?,
+                self.number(number, x)?,
+                self.number(number, y)?,
+            ),
+            ("position", [who, x, y, map_id]) => ScriptKind::CharacterChangePosition(
+                self.character(number, who)?,
+                self.number(number, x)?,
+                self.number(number, y)?,
+                self.number(number, map_id)?,
+            ),
+            ("set", [name, ..]) => {
+                let value = self.ron::<Value>(number, rest[name.len()..].trim())?;
+                ScriptKind::SetVariable(String::from(*name), value)
+            }
+            ("add", [name, i]) => {
+                ScriptKind::AddVariable(String::from(*name), self.number(number, i)?)
+            }
+            ("clear", [name]) => ScriptKind::ClearVariable(String::from(*name)),
+            ("label", [name]) => ScriptKind::Label(String::from(*name)),
+            ("goto", [name]) => ScriptKind::Goto(String::from(*name)),
+            ("call", [id]) => ScriptKind::Call(self.number(number, id)?),
+            ("give", [who, item_id, quantity]) => ScriptKind::GiveItem(
+                self.character(number, who)?,
+                self.number(number, item_id)?,
+                self.number(number, quantity)?,
+            ),
+            ("take", [who, item_id, quantity]) => ScriptKind::TakeItem(
+                self.character(number, who)?,
+                self.number(number, item_id)?,
+                self.number(number, quantity)?,
+            ),
+            ("gold", [who, i]) => {
+                ScriptKind::AddGold(self.character(number, who)?, self.number(number, i)?)
+            }
+            ("wood", [who, i]) => {
+                ScriptKind::AddWood(self.character(number, who)?, self.number(number, i)?)
+            }
+            ("heal", [who, i]) => {
+                ScriptKind::Heal(self.character(number, who)?, self.number(number, i)?)
+            }
+            ("damage", [who, i]) => {
+                ScriptKind::Damage(self.character(number, who)?, self.number(number, i)?)
+            }
+            ("status", [who, kind, duration]) => ScriptKind::ApplyStatus(
+                self.character(number, who)?,
+                self.ron(number, kind)?,
+                self.number(number, duration)?,
+            ),
+            ("exp", [who, i]) => {
+                ScriptKind::GainExp(self.character(number, who)?, self.number(number, i)?)
+            }
+            ("controller", [who, ..]) => ScriptKind::SetController(
+                self.character(number, who)?,
+                self.ron(number, rest[who.len()..].trim())?,
+            ),
+            ("spawn", [template_id, x, y, map_id]) => ScriptKind::Spawn(
+                self.number(number, template_id)?,
+                self.position(number, x, y, map_id)?,
+            ),
+            ("focus", [who, seconds]) => {
+                ScriptKind::CameraFocus(self.character(number, who)?, self.number(number, seconds)?)
+            }
+            ("pan", [x, y, map_id, seconds]) => ScriptKind::CameraPan(
+                self.position(number, x, y, map_id)?,
+                self.number(number, seconds)?,
+            ),
+            ("camera_reset", [seconds]) => ScriptKind::CameraReset(self.number(number, seconds)?),
+            ("shake", [intensity, seconds]) => ScriptKind::Shake(
+                self.number(number, intensity)?,
+                self.number(number, seconds)?,
+            ),
+            ("fade", [r, g, b, a, seconds]) => ScriptKind::Fade(
+                self.number(number, r)?,
+                self.number(number, g)?,
+                self.number(number, b)?,
+                self.number(number, a)?,
+                self.number(number, seconds)?,
+            ),
+            ("wait", [seconds]) => ScriptKind::Wait(self.number(number, seconds)?),
+            ("animation", [x, y, map_id, seconds, ..]) => {
+                let animation = skip_words(rest, 4);
+                ScriptKind::PlayAnimation(
+                    self.ron(number, animation)?,
+                    self.position(number, x, y, map_id)?,
+                    self.number(number, seconds)?,
+                )
+            }
+            ("quest_start", [quest_id]) => ScriptKind::StartQuest(self.number(number, quest_id)?),
+            ("quest_advance", [quest_id]) => {
+                ScriptKind::AdvanceQuest(self.number(number, quest_id)?)
+            }
+            ("quest_complete", [quest_id]) => {
+                ScriptKind::CompleteQuest(self.number(number, quest_id)?)
+            }
+            _ => return Err(self.error(number, &format!("unknown command `{}`", content))),
+        };
+        Ok(s)
+    }
+
+    fn number<T: std::str::FromStr>(&self, line: usize, s: &str) -> Result<T, ParseError> {
+        s.parse()
+            .map_err(|_| self.error(line, &format!("expected a number, found `{}`", s)))
+    }
+
+    fn position(
+        &self,
+        line: usize,
+        x: &str,
+        y: &str,
+        map_id: &str,
+    ) -> Result<Position, ParseError> {
+        Ok(Position {
+            map_id: self.number(line, map_id)?,
+            x: self.number(line, x)?,
+            y: self.number(line, y)?,
+        })
+    }
+
+    fn character(&self, line: usize, s: &str) -> Result<ScriptCharacter, ParseError> {
+        match s {
+            "initiator" => Ok(ScriptCharacter::Initiator),
+            "target" => Ok(ScriptCharacter::Target),
+            _ => Ok(ScriptCharacter::Character(self.number(line, s)?)),
+        }
+    }
+
+    fn ron<T: serde::de::DeserializeOwned>(&self, line: usize, s: &str) -> Result<T, ParseError> {
+        ron::de::from_str(s).map_err(|e| self.error(line, &format!("{} in `{}`", e, s)))
+    }
+}
+
+fn is_option(content: &str) -> bool {
+    content.starts_with("- ") || content == "-"
+}
+
+/// What follows the first `n` words
+fn skip_words(s: &str, n: usize) -> &str {
+    let mut s = s.trim_start();
+    for _ in 0..n {
+        let end = s.find(char::is_whitespace).unwrap_or(s.len());
+        s = s[end..].trim_start();
+    }
+    s
+}
+
+fn speaker(s: &str) -> Speaker {
+    match s {
+        "initiator" => Speaker::Character(ScriptCharacter::Initiator),
+        "target" => Speaker::Character(ScriptCharacter::Target),
+        _ => match (s.parse(), s.find('|')) {
+            (Ok(id), _) => Speaker::Character(ScriptCharacter::Character(id)),
+            (Err(_), Some(i)) => {
+                Speaker::Named(String::from(&s[..i]), Some(String::from(&s[i + 1..])))
+            }
+            (Err(_), None) => Speaker::Named(String::from(s), None),
+        },
+    }
+}
+
+fn export_speaker(speaker: &Speaker) -> String {
+    match speaker {
+        Speaker::Character(c) => export_character(c),
+        Speaker::Named(name, None) => name.clone(),
+        Speaker::Named(name, Some(portrait)) => format!("{}|{}", name, portrait),
+    }
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("\\n", "\n")
+}
+
+fn export_text(s: &str) -> String {
+    let s = s.replace('\n', "\\n");
+    let plain = !(s.is_empty()
+        || s.starts_with(&['@', '-', '#', '[', '\\'][..])
+        || s.starts_with(char::is_whitespace));
+    if plain {
+        s
+    } else {
+        format!("\\{}", s)
+    }
+}
+
+fn export_character(c: &ScriptCharacter) -> String {
+    match c {
+        ScriptCharacter::Initiator => String::from("initiator"),
+        ScriptCharacter::Target => String::from("target"),
+        ScriptCharacter::Character(id) => id.to_string(),
+    }
+}
+
+fn export_ron<T: Serialize>(value: &T) -> String {
+    ron::ser::to_string(value).unwrap_or_default()
+}
+
+fn export_block(s: &mut String, scripts: &[ScriptKind], depth: usize) {
+    let indent = INDENT.repeat(depth);
+    for (i, script) in scripts.iter().enumerate() {
+        let line = match script {
+            ScriptKind::Text(text) => export_text(text),
+            ScriptKind::Say(speaker, text) => {
+                format!(
+                    "[{}] {}",
+                    export_speaker(speaker),
+                    text.replace('\n', "\\n")
+                )
+            }
+            ScriptKind::Background(Background::None) => String::from("@background none"),
+            ScriptKind::Background(Background::Color(r, g, b, a)) => {
+                format!("@background color {} {} {} {}", r, g, b, a)
+            }
+            ScriptKind::Background(Background::Image(path)) => {
+                format!("@background image {}", path)
+            }
+            ScriptKind::If(condition, v) => {
+                s.push_str(&format!("{}@if {}\n", indent, export_ron(condition)));
+                export_block(s, v, depth + 1);
+                continue;
+            }
+            ScriptKind::IfElse(condition, v, e) => {
+                s.push_str(&format!("{}@if {}\n", indent, export_ron(condition)));
+                export_block(s, v, depth + 1);
+                s.push_str(&format!("{}@else\n", indent));
+                export_block(s, e, depth + 1);
+                continue;
+            }
+            ScriptKind::Select(options) => {
+                // Keep it apart from the options of the select before
+                if i > 0 && matches!(scripts[i - 1], ScriptKind::Select(_)) {
+                    s.push_str(&format!("{}@select\n", indent));
+                }
+                for option in options {
+                    let text = option.text.replace('\n', "\\n");
+                    s.push_str(&format!("{}- {}\n", indent, text));
+                    if let Some(condition) = &option.visible {
+                        s.push_str(&format!(
+                            "{}{}@visible {}\n",
+                            indent,
+                            INDENT,
+                            export_ron(condition)
+                        ));
+                    }
+                    if let Some((condition, reason)) = &option.enabled {
+                        let condition = export_ron(condition);
+                        s.push_str(&format!(
+                            "{}{}@enabled {} | {}\n",
+                            indent, INDENT, condition, reason
+                        ));
+                    }
+                    export_block(s, &option.scripts, depth + 1);
+                }
+                continue;
+            }
+            ScriptKind::SelectAbility(v) => {
+                s.push_str(&format!("{}@select_ability\n", indent));
+                export_block(s, v, depth + 1);
+                continue;
+            }
+            ScriptKind::Restart => String::from("@restart"),
+            ScriptKind::Shop => String::from("@shop"),
+            ScriptKind::Surrender => String::from("@surrender"),
+            ScriptKind::End => String::from("@end"),
+            ScriptKind::ForgetSkill => String::from("@forget_skill"),
+            ScriptKind::PracticeSkill => String::from("@practice_skill"),
+            ScriptKind::CharacterMove(c, x, y) => {
+                format!("@move {} {} {}", export_character(c), x, y)
+            }
+            ScriptKind::CharacterChangePosition(c, x, y, map_id) => {
+                format!("@position {} {} {} {}", export_character(c), x, y, map_id)
+            }
+            ScriptKind::SetVariable(name, value) => format!("@set {} {}", name, export_ron(value)),
+            ScriptKind::AddVariable(name, i) => format!("@add {} {}", name, i),
+            ScriptKind::ClearVariable(name) => format!("@clear {}", name),
+            ScriptKind::Label(name) => format!("@label {}", name),
+            ScriptKind::Goto(name) => format!("@goto {}", name),
+            ScriptKind::Call(id) => format!("@call {}", id),
+            ScriptKind::GiveItem(c, item_id, quantity) => {
+                format!("@give {} {} {}", export_character(c), item_id, quantity)
+            }
+            ScriptKind::TakeItem(c, item_id, quantity) => {
+                format!("@take {} {} {}", export_character(c), item_id, quantity)
+            }
+            ScriptKind::AddGold(c, i) => format!("@gold {} {}", export_character(c), i),
+            ScriptKind::AddWood(c, i) => format!("@wood {} {}", export_character(c), i),
+            ScriptKind::Heal(c, i) => format!("@heal {} {}", export_character(c), i),
+            ScriptKind::Damage(c, i) => format!("@damage {} {}", export_character(c), i),
+            ScriptKind::ApplyStatus(c, kind, duration) => format!(
+                "@status {} {} {}",
+                export_character(c),
+                export_ron(kind),
+                duration
+            ),
+            ScriptKind::GainExp(c, i) => format!("@exp {} {}", export_character(c), i),
+            ScriptKind::SetController(c, controller) => {
+                format!(
+                    "@controller {} {}",
+                    export_character(c),
+                    export_ron(controller)
+                )
+            }
+            ScriptKind::CameraFocus(c, seconds) => {
+                format!("@focus {} {}", export_character(c), seconds)
+            }
+            ScriptKind::CameraPan(p, seconds) => {
+                format!("@pan {} {} {} {}", p.x, p.y, p.map_id, seconds)
+            }
+            ScriptKind::CameraReset(seconds) => format!("@camera_reset {}", seconds),
+            ScriptKind::Shake(intensity, seconds) => format!("@shake {} {}", intensity, seconds),
+            ScriptKind::Fade(r, g, b, a, seconds) => {
+                format!("@fade {} {} {} {} {}", r, g, b, a, seconds)
+            }
+            ScriptKind::Wait(seconds) => format!("@wait {}", seconds),
+            ScriptKind::PlayAnimation(animation, p, seconds) => format!(
+                "@animation {} {} {} {} {}",
+                p.x,
+                p.y,
+                p.map_id,
+                seconds,
+                export_ron(animation)
+            ),
+            ScriptKind::StartQuest(quest_id) => format!("@quest_start {}", quest_id),
+            ScriptKind::AdvanceQuest(quest_id) => format!("@quest_advance {}", quest_id),
+            ScriptKind::CompleteQuest(quest_id) => format!("@quest_complete {}", quest_id),
+            ScriptKind::Spawn(template_id, p) => {
+                format!("@spawn {} {} {} {}", template_id, p.x, p.y, p.map_id)
+            }
+        };
+        s.push_str(&indent);
+        s.push_str(&line);
+        s.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing;
+    use super::*;
+
+    fn round_trip(script: &Script) {
+        let source = script.export();
+        let compiled = Script::compile(script.id, "export", &source)
+            .unwrap_or_else(|e| panic!("{}\n{}", e, source));
+        assert_eq!(export_ron(&compiled.scripts), export_ron(&script.scripts));
+        assert_eq!(compiled.export(), source);
+    }
+
+    #[test]
+    fn shipped_scripts_round_trip() {
+        let game = testing::game();
+        assert!(!game.novel_system.scripts.is_empty());
+        for script in game.novel_system.scripts.values() {
+            round_trip(script);
+        }
+    }
+
+    #[test]
+    fn adjacent_selects_round_trip() {
+        let source = "- A\n    One\n@select\n- B\n    Two\n- C\n";
+        let script = Script::compile(1, "test", source).unwrap();
+        assert_eq!(script.scripts.len(), 2);
+        assert_eq!(script.export(), source);
+        round_trip(&script);
+    }
+
+    #[test]
+    fn select_without_options() {
+        let error = Script::compile(1, "test", "@select\nHello\n").unwrap_err();
+        assert_eq!(error.line, 1);
+    }
+
+    #[test]
+    fn load_scripts_by_file_stem() {
+        let mut novel_system = testing::game().novel_system;
+        novel_system.scripts.clear();
+        let paths = ["7.script", "notes.txt", "guide.script", "8.script"];
+        let errors = novel_system.load_scripts(paths, |path| match path.to_str() {
+            Some("8.script") => Ok(String::from("@select\n")),
+            _ => Ok(String::from("Hello\n")),
+        });
+        assert_eq!(novel_system.scripts.keys().collect::<Vec<_>>(), vec![&7]);
+        assert_eq!(novel_system.scripts[&7].id, 7);
+        assert_eq!(
+            errors,
+            vec![
+                String::from("Script file name is not an id: guide.script"),
+                String::from("8.script:1: @select without options"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use super::{CrowdControlKind, Position};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Events kept, older ones are dropped
+pub const MAX_EVENTS: usize = 200;
+
+/// Something that happened in a fight or to a character, for the combat log
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum GameEvent {
+    Damaged {
+        target_id: i32,
+        source_id: Option<i32>,
+        amount: i32,
+    },
+    Healed {
+        target_id: i32,
+        source_id: Option<i32>,
+        amount: i32,
+    },
+    /// An attack on a tile hit no one
+    Missed {
+        source_id: i32,
+        position: Position,
+    },
+    StatusApplied {
+        target_id: i32,
+        source_id: Option<i32>,
+        kind: CrowdControlKind,
+        duration: i32,
+    },
+    ExperienceDrained {
+        target_id: i32,
+        source_id: i32,
+        amount: i32,
+    },
+    LevelUp {
+        character_id: i32,
+        level: i32,
+    },
+    Defeated {
+        target_id: i32,
+        source_id: Option<i32>,
+    },
+    ItemGained {
+        character_id: i32,
+        item_id: i32,
+        quantity: i32,
+    },
+}
+
+/// Events in the order they happened
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct EventQueue {
+    /// Oldest first, at most MAX_EVENTS
+    pub events: VecDeque<GameEvent>,
+    /// Events pushed since the start, dropped ones included
+    pub count: usize,
+}
+
+impl EventQueue {
+    pub fn push(&mut self, event: GameEvent) {
+        log::debug!("{:?}", event);
+        self.events.push_back(event);
+        if self.events.len() > MAX_EVENTS {
+            self.events.pop_front();
+        }
+        self.count += 1;
+    }
+
+    /// Events pushed after `count` events, as far as they are kept
+    pub fn since(&self, count: usize) -> impl Iterator<Item = &GameEvent> {
+        let new = self.count.saturating_sub(count).min(self.events.len());
+        self.events.iter().skip(self.events.len() - new)
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level_up(level: i32) -> GameEvent {
+        GameEvent::LevelUp {
+            character_id: 1,
+            level,
+        }
+    }
+
+    #[test]
+    fn since() {
+        let mut queue = EventQueue::default();
+        queue.push(level_up(1));
+        let seen = queue.count;
+        queue.push(level_up(2));
+        queue.push(level_up(3));
+        let new: Vec<_> = queue.since(seen).cloned().collect();
+        assert_eq!(new, vec![level_up(2), level_up(3)]);
+        assert_eq!(queue.since(queue.count).count(), 0);
+    }
+
+    #[test]
+    fn since_dropped_events() {
+        let mut queue = EventQueue::default();
+        for level in 0..MAX_EVENTS as i32 + 10 {
+            queue.push(level_up(level));
+        }
+        assert_eq!(queue.events.len(), MAX_EVENTS);
+        assert_eq!(queue.since(0).count(), MAX_EVENTS);
+        assert_eq!(queue.since(0).next(), Some(&level_up(10)));
+    }
+
+    #[test]
+    fn clear_keeps_count() {
+        let mut queue = EventQueue::default();
+        queue.push(level_up(1));
+        let seen = queue.count;
+        queue.clear();
+        queue.push(level_up(2));
+        assert_eq!(queue.count, 2);
+        let new: Vec<_> = queue.since(seen).cloned().collect();
+        assert_eq!(new, vec![level_up(2)]);
+    }
+}
@@ -0,0 +1,134 @@
+use super::{Controller, Game, Position};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Tiles seen by the player characters, for the maps
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ExplorationSystem {
+    pub explored: HashSet<Position>,
+    /// Where each player character last looked around
+    #[serde(skip)]
+    seen_from: HashMap<i32, Position>,
+}
+
+impl ExplorationSystem {
+    /// Reveal the tiles around player characters that moved
+    pub fn update(game: &mut Game) {
+        let moved: Vec<(i32, Position)> = game
+            .characters
+            .iter()
+            .filter(|(_, c)| matches!(c.controller, Controller::Player) && !c.hidden)
+            .filter(|(id, c)| game.exploration_system.seen_from.get(id) != Some(&c.position))
+            .map(|(id, c)| (*id, c.position))
+            .collect();
+        for (character_id, position) in moved {
+            ExplorationSystem::reveal(game, &position);
+            game.exploration_system
+                .seen_from
+                .insert(character_id, position);
+        }
+    }
+
+    /// Mark the tiles in sight of `from` as explored
+    pub fn reveal(game: &mut Game, from: &Position) {
+        let range = game.camera.extend;
+        let mut seen = Vec::new();
+        for y in from.y - range..=from.y + range {
+            for x in from.x - range..=from.x + range {
+                let position = Position {
+                    map_id: from.map_id,
+                    x,
+                    y,
+                };
+                if game.tile_at_position(&position).is_some() && game.line_of_sight(from, &position)
+                {
+                    seen.push(position);
+                }
+            }
+        }
+        game.exploration_system.explored.extend(seen);
+    }
+
+    pub fn is_explored(&self, position: &Position) -> bool {
+        self.explored.contains(position)
+    }
+
+    /// Whether a player character sees the tile right now
+    pub fn is_visible(game: &Game, position: &Position) -> bool {
+        let range = game.camera.extend;
+        game.characters
+            .values()
+            .filter(|c| matches!(c.controller, Controller::Player) && !c.hidden)
+            .any(|c| {
+                c.position.map_id == position.map_id
+                    && (c.position.x - position.x).abs() <= range
+                    && (c.position.y - position.y).abs() <= range
+                    && game.line_of_sight(&c.position, position)
+            })
+    }
+
+    /// Forget the map, when it is entered again as a new floor
+    pub fn forget(&mut self, map_id: i32) {
+        self.explored.retain(|p| p.map_id != map_id);
+        self.seen_from.retain(|_, p| p.map_id != map_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{testing, CharacterCreation, Race};
+    use super::*;
+
+    fn at(x: i32, y: i32) -> Position {
+        Position { map_id: 99, x, y }
+    }
+
+    #[test]
+    fn reveal() {
+        let mut game = testing::game();
+        testing::add_map(&mut game, 99, &["........", "..#.....", "........"]);
+        ExplorationSystem::reveal(&mut game, &at(0, 1));
+        let explored = &game.exploration_system;
+        assert!(explored.is_explored(&at(1, 1)));
+        // The wall is seen, not what it hides
+        assert!(explored.is_explored(&at(2, 1)));
+        assert!(!explored.is_explored(&at(3, 1)));
+        assert!(explored.is_explored(&at(4, 0)));
+        // Out of range, camera extend is 5
+        assert!(!explored.is_explored(&at(6, 0)));
+        // Off the map
+        assert!(!explored.is_explored(&at(-1, 1)));
+    }
+
+    #[test]
+    fn forget() {
+        let mut game = testing::game();
+        testing::add_map(&mut game, 99, &["....."]);
+        ExplorationSystem::reveal(&mut game, &at(0, 0));
+        let town = Position {
+            map_id: 0,
+            x: 1,
+            y: 1,
+        };
+        ExplorationSystem::reveal(&mut game, &town);
+        game.exploration_system.forget(99);
+        assert!(!game.exploration_system.is_explored(&at(1, 0)));
+        assert!(game.exploration_system.is_explored(&town));
+    }
+
+    #[test]
+    fn new_game_forgets_everything() {
+        let mut game = testing::game();
+        testing::add_map(&mut game, 99, &["....."]);
+        ExplorationSystem::reveal(&mut game, &at(0, 0));
+        let creation = CharacterCreation {
+            name: String::from("Hero"),
+            race: Race::Human,
+            class_id: 1,
+            character_sprite_id: 1,
+            points: Default::default(),
+        };
+        game.new_game(&creation).unwrap();
+        assert!(!game.exploration_system.is_explored(&at(1, 0)));
+    }
+}
@@ -5,6 +5,8 @@ pub const TEMP_MAP_ID: i32 = -1;
 pub const BOSS_MAP_ID: i32 = -2;
 pub const NORMAL_MAP_ID: i32 = -3;
 pub const SKILL_VENDOR_ID: i32 = -1;
+/// Script started with the boss of every fifth floor
+pub const BOSS_TALK_ID: i32 = 0;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Current {
@@ -31,6 +33,9 @@ impl FloorSystem {
         if let Some(current) = &mut game.floor_system.current {
             let floor = current.floor + 1;
             current.floor = floor;
+            // Floors reuse the maps, explore them again
+            game.exploration_system.forget(NORMAL_MAP_ID);
+            game.exploration_system.forget(BOSS_MAP_ID);
 
             if let Some(character) = game.characters.get_mut(&current.character_id) {
                 character.experience += 1;
@@ -117,8 +122,7 @@ impl FloorSystem {
                         enemy.controller = super::Controller::Boss { waiting: true };
                     }
                     // Talk to player
-                    let talk_id = 0;
-                    game.start_novel(talk_id, character_id, id);
+                    game.start_novel(BOSS_TALK_ID, character_id, id);
                 }
                 _ => {
                     log::error!("Floor out of range");
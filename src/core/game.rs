@@ -1,11 +1,15 @@
+use pathfinding::directed::astar::astar;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::{
-    tile_map::TileSheet, Action, AnimationEffect, Area, AreaSystem, Attribute, Character,
-    CharacterAction, CharacterCrowdControl, CharacterSprite, Command, Controller, CurrentShop,
-    FloorSystem, Item, NovelSystem, Position, Race, ShopSystem, SpriteSequence, SpriteSheet,
-    Target, Teleportation, Tile, TileMap, Turn, TurnKind, TurnSystem,
+    tile_map::{TileSheet, MAX_CLIMB},
+    Action, AnimationEffect, Area, AreaSystem, Attribute, Character, CharacterAction,
+    CharacterCreation, CharacterCrowdControl, CharacterSprite, CharacterTemplate, Command,
+    Controller, CreationError, CurrentShop, EventQueue, ExplorationSystem, FloorSystem, Item,
+    Localization, NovelSystem, PlayerClass, Position, Quest, QuestEvent, QuestSystem, Race,
+    ShopSystem, SpriteSequence, SpriteSheet, Target, Teleportation, Tile, TileMap, Turn, TurnKind,
+    TurnSystem, VariableSystem, PLAYER_START,
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -14,12 +18,108 @@ pub struct Camera {
     pub extend: i32,
     pub tile_size: i32,
     pub border: i32,
+    /// Shown instead of the character, for cutscenes
+    pub focus: Option<CameraFocus>,
+    pub shake: Option<CameraShake>,
+    pub fade: Option<CameraFade>,
+}
+
+impl Camera {
+    pub fn update(&mut self, delta: f32) {
+        if let Some(focus) = &mut self.focus {
+            focus.elapsed += delta;
+            if focus.release && focus.elapsed >= focus.duration {
+                self.focus = None;
+            }
+        }
+        if let Some(shake) = &mut self.shake {
+            shake.elapsed += delta;
+            if shake.elapsed >= shake.duration {
+                self.shake = None;
+            }
+        }
+        if let Some(fade) = &mut self.fade {
+            fade.elapsed += delta;
+            if fade.elapsed >= fade.duration && fade.to == 0 {
+                self.fade = None;
+            }
+        }
+    }
+
+    /// Offset in tiles of the shaking screen
+    pub fn shake_offset(&self) -> (f32, f32) {
+        match &self.shake {
+            Some(shake) => {
+                let t = shake.elapsed;
+                let strength = shake.intensity * (1.0 - progress(t, shake.duration));
+                ((t * 61.0).sin() * strength, (t * 43.0).cos() * strength)
+            }
+            None => (0.0, 0.0),
+        }
+    }
+}
+
+/// Fraction of `duration` done after `elapsed`, instant for 0 duration
+fn progress(elapsed: f32, duration: f32) -> f32 {
+    if duration <= 0.0 {
+        1.0
+    } else {
+        (elapsed / duration).min(1.0)
+    }
+}
+
+/// Pan from one tile to another
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CameraFocus {
+    pub map_id: i32,
+    pub from: (f32, f32),
+    pub to: (f32, f32),
+    pub elapsed: f32,
+    pub duration: f32,
+    /// Follow the character again once there
+    pub release: bool,
+}
+
+impl CameraFocus {
+    pub fn center(&self) -> (f32, f32) {
+        let t = progress(self.elapsed, self.duration);
+        (
+            self.from.0 + (self.to.0 - self.from.0) * t,
+            self.from.1 + (self.to.1 - self.from.1) * t,
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CameraShake {
+    /// Tiles
+    pub intensity: f32,
+    pub elapsed: f32,
+    pub duration: f32,
+}
+
+/// Color over the screen going from one alpha to another
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CameraFade {
+    pub color: (u8, u8, u8),
+    pub from: u8,
+    pub to: u8,
+    pub elapsed: f32,
+    pub duration: f32,
+}
+
+impl CameraFade {
+    pub fn alpha(&self) -> u8 {
+        let t = progress(self.elapsed, self.duration);
+        (self.from as f32 + (self.to as f32 - self.from as f32) * t) as u8
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Game {
     pub characters: HashMap<i32, Character>,
     pub character_actions: HashMap<i32, CharacterAction>,
+    pub character_templates: HashMap<i32, CharacterTemplate>,
     pub commands: HashMap<i32, Command>,
     pub actions: HashMap<i32, Action>,
     pub items: HashMap<i32, Item>,
@@ -32,11 +132,21 @@ pub struct Game {
     pub sprite_sequences: HashMap<i32, SpriteSequence>,
     pub sprite_animations: HashMap<i32, AnimationEffect>,
     pub areas: HashMap<i32, Area>,
+    pub player_classes: HashMap<i32, PlayerClass>,
+    pub quests: HashMap<i32, Quest>,
     pub camera: Camera,
     pub turn_system: TurnSystem,
     pub novel_system: NovelSystem,
     pub shop_system: ShopSystem,
     pub floor_system: FloorSystem,
+    pub variable_system: VariableSystem,
+    pub quest_system: QuestSystem,
+    pub localization: Localization,
+    /// Filled as the game runs, neither loaded nor saved
+    #[serde(skip)]
+    pub events: EventQueue,
+    #[serde(default)]
+    pub exploration_system: ExplorationSystem,
 }
 
 impl Game {
@@ -78,9 +188,14 @@ impl Game {
                     .map(|a| a.id)
             })
             .unwrap();
-        let new_command = Command::new(self, character_id, action_id, target);
-        if let Ok(command) = new_command {
-            self.set_character_command(character_id, command);
+        match Command::new(self, character_id, action_id, target) {
+            Ok(command) => self.set_character_command(character_id, command),
+            // The turn waits for a command, so the character idles instead
+            Err(e) if action_name != "Idle" => {
+                log::warn!("{} for character {}: {:?}", action_name, character_id, e);
+                self.add_command(character_id, String::from("Idle"), Target::None);
+            }
+            Err(e) => log::warn!("Idle for character {}: {:?}", character_id, e),
         }
     }
 
@@ -91,9 +206,62 @@ impl Game {
         }
     }
 
+    /// Map and tile the camera is centered on, shake included
+    pub fn camera_center(&self) -> Option<(i32, f32, f32)> {
+        let (map_id, x, y) = match &self.camera.focus {
+            Some(focus) => {
+                let (x, y) = focus.center();
+                (focus.map_id, x, y)
+            }
+            None => {
+                let c = self.characters.get(&self.camera.character_id)?;
+                (c.position.map_id, c.position.x as f32, c.position.y as f32)
+            }
+        };
+        let (dx, dy) = self.camera.shake_offset();
+        Some((map_id, x + dx, y + dy))
+    }
+
+    /// Tile under a point of the screen, None outside the map view
+    pub fn position_at(&self, x: f32, y: f32) -> Option<Position> {
+        let (map_id, center_x, center_y) = self.camera_center()?;
+        let tile_size = self.camera.tile_size as f32;
+        let extend = self.camera.extend as f32;
+        let border = self.camera.border as f32;
+        let size = (2.0 * extend + 1.0) * tile_size;
+        if x < border || y < border || x >= border + size || y >= border + size {
+            return None;
+        }
+        Some(Position {
+            map_id,
+            x: ((x - border) / tile_size + center_x - extend).floor() as i32,
+            y: ((y - border) / tile_size + center_y - extend).floor() as i32,
+        })
+    }
+
+    /// Pan the camera to the tile, follow the character again if `release`
+    pub fn camera_pan(&mut self, position: Position, duration: f32, release: bool) {
+        let from = match self.camera_center() {
+            Some((map_id, x, y)) if map_id == position.map_id => {
+                let (dx, dy) = self.camera.shake_offset();
+                (x - dx, y - dy)
+            }
+            _ => (position.x as f32, position.y as f32),
+        };
+        self.camera.focus = Some(CameraFocus {
+            map_id: position.map_id,
+            from,
+            to: (position.x as f32, position.y as f32),
+            elapsed: 0.0,
+            duration,
+            release,
+        });
+    }
+
     // Novel System
     pub fn start_novel(&mut self, id: i32, character_id: i32, target_id: i32) {
         self.turn_system.pause = true;
+        QuestSystem::notify(self, QuestEvent::Talked(character_id, target_id));
         NovelSystem::start(self, id, character_id, target_id);
     }
 
@@ -141,6 +309,9 @@ impl Game {
     pub fn update(&mut self, delta: f32) {
         TurnSystem::update(self, delta);
         NovelSystem::update(self, delta);
+        QuestSystem::update(self);
+        ExplorationSystem::update(self);
+        self.camera.update(delta);
     }
 
     pub fn character_set_position(&mut self, character_id: i32, position: Position) {
@@ -185,18 +356,140 @@ impl Game {
             .map(|(id, _)| *id)
     }
 
-    pub fn new_player_character(&mut self) {
-        let mut character_action_ids = Vec::new();
-        for action_id in 1..=4 {
-            let id = 1 + *self.character_actions.keys().max().unwrap_or(&0);
-            let v = CharacterAction::new(action_id);
-            self.character_actions.insert(id, v);
-            character_action_ids.push(id);
+    pub fn tile_at_position(&self, position: &Position) -> Option<&Tile> {
+        self.tile_maps
+            .get(&position.map_id)
+            .and_then(|m| m.tiles.get(position.y as usize))
+            .and_then(|r| r.get(position.x as usize))
+            .and_then(|id| self.tiles.get(id))
+    }
+
+    /// Whether a character standing on `from` can move onto `to`
+    pub fn can_step(&self, from: &Position, to: &Position) -> bool {
+        match (self.tile_at_position(from), self.tile_at_position(to)) {
+            (Some(a), Some(b)) => b.walkable && (b.elevation - a.elevation).abs() <= MAX_CLIMB,
+            (None, Some(b)) => b.walkable,
+            (_, None) => false,
+        }
+    }
+
+    pub fn line_of_sight(&self, from: &Position, to: &Position) -> bool {
+        self.line_clear(from, to, |t| !t.block_sight)
+    }
+
+    pub fn line_of_fire(&self, from: &Position, to: &Position) -> bool {
+        self.line_clear(from, to, |t| !t.block_projectile)
+    }
+
+    /// Walk the tiles between `from` and `to` (both excluded)
+    fn line_clear<F>(&self, from: &Position, to: &Position, clear: F) -> bool
+    where
+        F: Fn(&Tile) -> bool,
+    {
+        if from.map_id != to.map_id {
+            return false;
+        }
+        let dx = (to.x - from.x).abs();
+        let dy = -(to.y - from.y).abs();
+        let sx = if from.x < to.x { 1 } else { -1 };
+        let sy = if from.y < to.y { 1 } else { -1 };
+        let mut err = dx + dy;
+        let mut position = *from;
+        loop {
+            if position == *to {
+                return true;
+            }
+            if position != *from && !self.tile_at_position(&position).map(&clear).unwrap_or(true) {
+                return false;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                position.x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                position.y += sy;
+            }
+        }
+    }
+
+    /// First step (x, y) of the cheapest path from `from` to `to`
+    pub fn path_step(&self, from: &Position, to: &Position) -> Option<(i32, i32)> {
+        let (path, _) = astar(
+            from,
+            |p| {
+                let p = *p;
+                [(0, -1), (-1, 0), (0, 1), (1, 0)]
+                    .iter()
+                    .map(|(x, y)| Position {
+                        map_id: p.map_id,
+                        x: p.x + x,
+                        y: p.y + y,
+                    })
+                    .filter(|n| {
+                        n == to || (self.can_step(&p, n) && self.character_at_position(n).is_none())
+                    })
+                    .map(|n| {
+                        let cost = self.tile_at_position(&n).map(|t| t.cost).unwrap_or(1);
+                        (n, cost.max(1))
+                    })
+                    .collect::<Vec<_>>()
+            },
+            |p| Position::distance(p, to),
+            |p| p == to,
+        )?;
+        path.get(1).map(|p| (p.x - from.x, p.y - from.y))
+    }
+
+    /// Replace the player characters of the content with a new one and start
+    /// the floors with it, returns its id
+    pub fn new_game(&mut self, creation: &CharacterCreation) -> Result<i32, CreationError> {
+        creation.validate(self)?;
+        let players: Vec<i32> = self
+            .characters
+            .iter()
+            .filter(|(_, c)| matches!(c.controller, Controller::Player))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &players {
+            self.characters.remove(id);
+        }
+        let removed = |t: &Turn| match t.kind {
+            TurnKind::Character { character_id } | TurnKind::Respawn { character_id } => {
+                players.contains(&character_id)
+            }
+        };
+        self.turn_system.turn_queue.retain(|t| !removed(t));
+        if self.turn_system.current_turn.as_ref().map(removed) == Some(true) {
+            self.turn_system.current_turn = None;
         }
 
+        let id = self.new_player_character(creation)?;
+        self.camera.character_id = id;
+        self.events.clear();
+        self.exploration_system = Default::default();
+        FloorSystem::start(self, id);
+        Ok(id)
+    }
+
+    /// Create a player character in town from the choices, returns its id
+    pub fn new_player_character(
+        &mut self,
+        creation: &CharacterCreation,
+    ) -> Result<i32, CreationError> {
+        let class = creation.validate(self)?;
+        let points = &creation.points;
+        let attribute = |base| Attribute {
+            base,
+            drain: 0,
+            damage: 0,
+            modifier: 0,
+        };
         let id = 1 + *self.characters.keys().max().unwrap_or(&0);
-        let c = Character {
+        let mut c = Character {
             id,
+            name: String::from(creation.name.trim()),
             crowd_controls: CharacterCrowdControl {
                 stun: 0,
                 charm: 0,
@@ -207,64 +500,142 @@ impl Game {
                 root: 0,
                 silent: 0,
             },
-            position: Position {
-                map_id: 0,
-                x: 5,
-                y: 8,
-            },
+            position: PLAYER_START,
             controller: Controller::Player,
-            items: Default::default(),
-            character_action_ids,
-            race: Race::Human,
-            character_sprite_id: 1,
+            items: class.items.clone(),
+            equipment: Vec::new(),
+            character_action_ids: Vec::new(),
+            race: creation.race,
+            character_sprite_id: creation.character_sprite_id,
             offset_x: 0.0,
             offset_y: 0.0,
             defeated: false,
             dead: false,
-            experience: 8945,
-            level: 20,
-            hp: Attribute {
-                base: 20,
-                drain: 0,
-                damage: 0,
-                modifier: 0,
-            },
-            mp: Attribute {
-                base: 20,
-                drain: 0,
-                damage: 0,
-                modifier: 0,
-            },
-            attack: Attribute {
-                base: 10,
-                drain: 0,
-                damage: 0,
-                modifier: 0,
-            },
-            defence: Attribute {
-                base: 5,
-                drain: 0,
-                damage: 0,
-                modifier: 0,
-            },
-            sanity: Attribute {
-                base: 10,
-                drain: 0,
-                damage: 0,
-                modifier: 0,
-            },
+            experience: class.experience,
+            level: class.level,
+            hp: attribute(class.hp + points.hp),
+            mp: attribute(class.mp + points.mp),
+            attack: attribute(class.attack + points.attack),
+            defence: attribute(class.defence + points.defence),
+            sanity: attribute(class.sanity + points.sanity),
             hidden: false,
             command_id: None,
             talk_id: 1,
+            dialogues: Vec::new(),
             shop: None,
-            gold: 0,
-            wood: 0,
+            gold: class.gold,
+            wood: class.wood,
+        };
+        for action_id in class.action_ids.clone() {
+            let character_action_id = 1 + *self.character_actions.keys().max().unwrap_or(&0);
+            self.character_actions
+                .insert(character_action_id, CharacterAction::new(action_id));
+            c.character_action_ids.push(character_action_id);
+        }
+        self.characters.insert(id, c);
+        self.add_turn(Turn {
+            time: 0,
+            kind: TurnKind::Character { character_id: id },
+        });
+        Ok(id)
+    }
+
+    /// Walkable tile without a character nearest to the position, the
+    /// position itself if free
+    pub fn free_tile_near(&self, position: &Position) -> Option<Position> {
+        let map = self.tile_maps.get(&position.map_id)?;
+        let height = map.tiles.len() as i32;
+        let width = map.tiles.iter().map(|row| row.len()).max().unwrap_or(0) as i32;
+        let free = |p: &Position| {
+            self.tile_at_position(p)
+                .map(|t| t.walkable)
+                .unwrap_or(false)
+                && self.character_at_position(p).is_none()
+        };
+        for range in 0..width.max(height) {
+            for y in position.y - range..=position.y + range {
+                for x in position.x - range..=position.x + range {
+                    // Only the ring at `range`, the inside was checked before
+                    if (x - position.x).abs() != range && (y - position.y).abs() != range {
+                        continue;
+                    }
+                    let p = Position {
+                        map_id: position.map_id,
+                        x,
+                        y,
+                    };
+                    if free(&p) {
+                        return Some(p);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Create a character from a template on the free tile nearest to the
+    /// position, returns its id, None without template or free tile
+    pub fn spawn_character(&mut self, template_id: i32, position: Position) -> Option<i32> {
+        let position = self.free_tile_near(&position)?;
+        let template = self.character_templates.get(&template_id)?;
+        let attribute = |base| Attribute {
+            base,
+            drain: 0,
+            damage: 0,
+            modifier: 0,
+        };
+        let id = 1 + *self.characters.keys().max().unwrap_or(&0);
+        let mut c = Character {
+            id,
+            name: template.name.clone(),
+            crowd_controls: CharacterCrowdControl {
+                stun: 0,
+                charm: 0,
+                shock: 0,
+                shocked: false,
+                poison: 0,
+                sleep: 0,
+                root: 0,
+                silent: 0,
+            },
+            position,
+            controller: template.controller,
+            items: template.items.clone(),
+            equipment: Vec::new(),
+            character_action_ids: Vec::new(),
+            race: template.race,
+            character_sprite_id: template.character_sprite_id,
+            offset_x: 0.0,
+            offset_y: 0.0,
+            defeated: false,
+            dead: false,
+            experience: template.experience,
+            level: template.level,
+            hp: attribute(template.hp),
+            mp: attribute(template.mp),
+            attack: attribute(template.attack),
+            defence: attribute(template.defence),
+            sanity: attribute(template.sanity),
+            hidden: false,
+            command_id: None,
+            talk_id: template.talk_id,
+            dialogues: template.dialogues.clone(),
+            shop: None,
+            gold: template.gold,
+            wood: template.wood,
         };
+        for action_id in template.action_ids.clone() {
+            let character_action_id = 1 + *self.character_actions.keys().max().unwrap_or(&0);
+            self.character_actions
+                .insert(character_action_id, CharacterAction::new(action_id));
+            c.character_action_ids.push(character_action_id);
+        }
         self.characters.insert(id, c);
         self.add_turn(Turn {
             time: 0,
             kind: TurnKind::Character { character_id: id },
         });
+        Some(id)
     }
 
     pub fn new_enemy(&mut self) -> i32 {
@@ -279,6 +650,7 @@ impl Game {
         let id = 1 + *self.characters.keys().max().unwrap_or(&0);
         let c = Character {
             id,
+            name: String::from("character.demon"),
             crowd_controls: CharacterCrowdControl {
                 stun: 0,
                 charm: 0,
@@ -296,6 +668,7 @@ impl Game {
             },
             controller: Controller::Enemy,
             items: Default::default(),
+            equipment: Vec::new(),
             character_action_ids,
             race: Race::Demon,
             character_sprite_id: 2,
@@ -338,6 +711,7 @@ impl Game {
             hidden: false,
             command_id: None,
             talk_id: 1,
+            dialogues: Vec::new(),
             shop: None,
             gold: 0,
             wood: 0,
@@ -350,3 +724,124 @@ impl Game {
         id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing;
+    use super::*;
+
+    fn command_action(game: &Game, character_id: i32) -> &str {
+        let command_id = game.characters[&character_id].command_id.unwrap();
+        &game.actions[&game.commands[&command_id].action_id].name
+    }
+
+    fn at(x: i32, y: i32) -> Position {
+        Position { map_id: 99, x, y }
+    }
+
+    #[test]
+    fn line_of_sight() {
+        let mut game = testing::game();
+        testing::add_map(&mut game, 99, &[".....", "..#..", "....."]);
+        assert!(game.line_of_sight(&at(0, 0), &at(4, 0)));
+        assert!(!game.line_of_sight(&at(0, 1), &at(4, 1)));
+        // The ends do not block
+        assert!(game.line_of_sight(&at(0, 1), &at(2, 1)));
+        assert!(game.line_of_sight(&at(2, 1), &at(4, 1)));
+        assert!(!game.line_of_sight(&at(0, 0), &at(4, 2)));
+        assert!(game.line_of_sight(&at(0, 2), &at(4, 2)));
+        let other_map = Position {
+            map_id: 0,
+            x: 4,
+            y: 0,
+        };
+        assert!(!game.line_of_sight(&at(0, 0), &other_map));
+    }
+
+    #[test]
+    fn line_of_fire_and_sight_differ() {
+        let mut game = testing::game();
+        testing::add_map(&mut game, 99, &["....."]);
+        // Glass, blocks projectiles but not sight
+        let glass: Tile =
+            ron::de::from_str("(walkable: false, block_projectile: true, kind: Image(\"/a.png\"))")
+                .unwrap();
+        game.tiles.insert(99, glass);
+        game.tile_maps.get_mut(&99).unwrap().tiles[0][2] = 99;
+        assert!(game.line_of_sight(&at(0, 0), &at(4, 0)));
+        assert!(!game.line_of_fire(&at(0, 0), &at(4, 0)));
+    }
+
+    /// Characters 2 and 3 on both sides of a wall, 2 only knowing Idle and Melee
+    fn blocked_game() -> Game {
+        let mut game = testing::game();
+        testing::add_map(&mut game, 99, &["..#.."]);
+        game.characters.get_mut(&2).unwrap().position = at(0, 0);
+        game.characters.get_mut(&3).unwrap().position = at(4, 0);
+        let mut character_action_ids = Vec::new();
+        for name in &["Idle", "Melee"] {
+            let action_id = game.actions.values().find(|a| a.name == *name).unwrap().id;
+            let id = 1 + *game.character_actions.keys().max().unwrap_or(&0);
+            game.character_actions
+                .insert(id, CharacterAction::new(action_id));
+            character_action_ids.push(id);
+        }
+        game.characters.get_mut(&2).unwrap().character_action_ids = character_action_ids;
+        game
+    }
+
+    #[test]
+    fn add_command_idles_when_blocked() {
+        let mut game = blocked_game();
+        game.add_command(2, String::from("Melee"), Target::Character(3));
+        assert_eq!(command_action(&game, 2), "Idle");
+    }
+
+    #[test]
+    fn add_command_in_sight() {
+        let mut game = blocked_game();
+        game.characters.get_mut(&3).unwrap().position = at(1, 0);
+        game.add_command(2, String::from("Melee"), Target::Character(3));
+        assert_eq!(command_action(&game, 2), "Melee");
+    }
+
+    #[test]
+    fn add_command_self_target_is_not_blocked() {
+        let mut game = blocked_game();
+        game.add_command(2, String::from("Melee"), Target::Character(2));
+        assert_eq!(command_action(&game, 2), "Melee");
+    }
+
+    #[test]
+    fn spawn_on_the_nearest_free_tile() {
+        let mut game = testing::game();
+        testing::add_map(&mut game, 99, &["..#", "..."]);
+        game.characters.get_mut(&2).unwrap().position = at(0, 0);
+        // Occupied
+        let id = game.spawn_character(0, at(0, 0)).unwrap();
+        assert_eq!(game.characters[&id].position, at(1, 0));
+        // Wall
+        let id = game.spawn_character(0, at(2, 0)).unwrap();
+        assert_eq!(game.characters[&id].position, at(1, 1));
+        let id = game.spawn_character(0, at(2, 1)).unwrap();
+        assert_eq!(game.characters[&id].position, at(2, 1));
+        let id = game.spawn_character(0, at(0, 0)).unwrap();
+        assert_eq!(game.characters[&id].position, at(0, 1));
+        // Full
+        assert!(game.spawn_character(0, at(0, 0)).is_none());
+    }
+
+    #[test]
+    fn position_at_inside_the_map_view() {
+        let mut game = testing::game();
+        let player = game.camera.character_id;
+        game.characters.get_mut(&player).unwrap().position = at(10, 10);
+        // Border 8, tile size 64, 5 tiles on each side of the center
+        assert_eq!(game.position_at(360.0, 360.0), Some(at(10, 10)));
+        assert_eq!(game.position_at(8.0, 711.0), Some(at(5, 15)));
+        assert_eq!(game.position_at(4.0, 360.0), None);
+        assert_eq!(game.position_at(712.0, 360.0), None);
+        assert_eq!(game.position_at(720.0, 360.0), None);
+        assert_eq!(game.position_at(360.0, 900.0), None);
+    }
+}
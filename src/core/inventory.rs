@@ -0,0 +1,168 @@
+use super::{CharacterAction, Game, ItemKind};
+use std::fmt;
+
+#[derive(Debug)]
+pub enum InventoryError {
+    NoCharacter(i32),
+    NoItem(i32),
+    /// The character has none of the item
+    NotHeld(i32),
+    /// Item equipped by the character, unequip it first
+    Equipped(i32),
+    NotEquipped(i32),
+    NotEquipment(i32),
+    /// AlreadyKnown(action_id), for action books
+    AlreadyKnown(i32),
+}
+
+impl fmt::Display for InventoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InventoryError::NoCharacter(id) => write!(f, "no character {}", id),
+            InventoryError::NoItem(id) => write!(f, "no item {}", id),
+            InventoryError::NotHeld(id) => write!(f, "item {} not held", id),
+            InventoryError::Equipped(id) => write!(f, "item {} is equipped", id),
+            InventoryError::NotEquipped(id) => write!(f, "item {} is not equipped", id),
+            InventoryError::NotEquipment(id) => write!(f, "item {} cannot be equipped", id),
+            InventoryError::AlreadyKnown(id) => write!(f, "action {} already known", id),
+        }
+    }
+}
+
+pub struct InventorySystem {}
+
+impl InventorySystem {
+    /// Drink a potion, read an action book or equip the item
+    pub fn use_item(
+        game: &mut Game,
+        character_id: i32,
+        item_id: i32,
+    ) -> Result<(), InventoryError> {
+        let kind = InventorySystem::held(game, character_id, item_id)?;
+        match kind {
+            ItemKind::Potion { hp, mp } => {
+                let (hp, mp) = (*hp, *mp);
+                if let Some(c) = game.characters.get_mut(&character_id) {
+                    c.take_item(item_id, 1);
+                    c.heal(hp);
+                    c.mp.damage = (c.mp.damage - mp).max(0);
+                }
+                Ok(())
+            }
+            ItemKind::ActionBook(action_id) => {
+                let action_id = *action_id;
+                let known = game
+                    .characters
+                    .get(&character_id)
+                    .map(|c| {
+                        c.character_action_ids
+                            .iter()
+                            .filter_map(|id| game.character_actions.get(id))
+                            .any(|ca| ca.action_id == action_id)
+                    })
+                    .unwrap_or(false);
+                if known {
+                    return Err(InventoryError::AlreadyKnown(action_id));
+                }
+                let id = 1 + *game.character_actions.keys().max().unwrap_or(&0);
+                game.character_actions
+                    .insert(id, CharacterAction::new(action_id));
+                if let Some(c) = game.characters.get_mut(&character_id) {
+                    c.take_item(item_id, 1);
+                    c.character_action_ids.push(id);
+                }
+                Ok(())
+            }
+            ItemKind::Equipment(..) => InventorySystem::equip(game, character_id, item_id),
+        }
+    }
+
+    /// Equip one of the item, the item in the same slot goes back to the items
+    pub fn equip(game: &mut Game, character_id: i32, item_id: i32) -> Result<(), InventoryError> {
+        let slot = match InventorySystem::held(game, character_id, item_id)? {
+            ItemKind::Equipment(slot, _) => *slot,
+            _ => return Err(InventoryError::NotEquipment(item_id)),
+        };
+        let replaced = game.characters.get(&character_id).and_then(|c| {
+            c.equipment.iter().copied().find(|id| {
+                matches!(
+                    game.items.get(id).map(|i| &i.item_kind),
+                    Some(ItemKind::Equipment(s, _)) if *s == slot
+                )
+            })
+        });
+        if let Some(replaced) = replaced {
+            InventorySystem::unequip(game, character_id, replaced)?;
+        }
+        if let Some(c) = game.characters.get_mut(&character_id) {
+            c.take_item(item_id, 1);
+            c.equipment.push(item_id);
+        }
+        InventorySystem::apply_modifiers(game, character_id, item_id, 1);
+        Ok(())
+    }
+
+    pub fn unequip(game: &mut Game, character_id: i32, item_id: i32) -> Result<(), InventoryError> {
+        let c = game
+            .characters
+            .get_mut(&character_id)
+            .ok_or(InventoryError::NoCharacter(character_id))?;
+        let index = c
+            .equipment
+            .iter()
+            .position(|id| *id == item_id)
+            .ok_or(InventoryError::NotEquipped(item_id))?;
+        c.equipment.remove(index);
+        c.give_item(item_id, 1);
+        InventorySystem::apply_modifiers(game, character_id, item_id, -1);
+        Ok(())
+    }
+
+    /// Throw away one of the item
+    pub fn drop_item(
+        game: &mut Game,
+        character_id: i32,
+        item_id: i32,
+    ) -> Result<(), InventoryError> {
+        let c = game
+            .characters
+            .get_mut(&character_id)
+            .ok_or(InventoryError::NoCharacter(character_id))?;
+        if c.take_item(item_id, 1) == 0 {
+            if c.equipment.contains(&item_id) {
+                return Err(InventoryError::Equipped(item_id));
+            }
+            return Err(InventoryError::NotHeld(item_id));
+        }
+        Ok(())
+    }
+
+    /// Kind of the item if the character has one
+    fn held(game: &Game, character_id: i32, item_id: i32) -> Result<&ItemKind, InventoryError> {
+        let c = game
+            .characters
+            .get(&character_id)
+            .ok_or(InventoryError::NoCharacter(character_id))?;
+        let item = game
+            .items
+            .get(&item_id)
+            .ok_or(InventoryError::NoItem(item_id))?;
+        if c.item_count(item_id) <= 0 {
+            return Err(InventoryError::NotHeld(item_id));
+        }
+        Ok(&item.item_kind)
+    }
+
+    /// Add the modifiers of the equipment `sign` times
+    fn apply_modifiers(game: &mut Game, character_id: i32, item_id: i32, sign: i32) {
+        let modifiers = match game.items.get(&item_id).map(|i| &i.item_kind) {
+            Some(ItemKind::Equipment(_, modifiers)) => modifiers,
+            _ => return,
+        };
+        if let Some(c) = game.characters.get_mut(&character_id) {
+            for (kind, value) in modifiers {
+                c.attribute_mut(*kind).modifier += sign * value;
+            }
+        }
+    }
+}
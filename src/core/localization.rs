@@ -0,0 +1,168 @@
+use super::{
+    Action, Character, CharacterTemplate, Item, PlayerClass, Quest, Race, ScriptKind, Speaker,
+    VariableSystem,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// String tables by language, player-facing text is written as keys into them
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Localization {
+    pub language: String,
+    /// Used for keys missing in `language`
+    pub fallback: String,
+    /// Language -> key -> text, loaded from `/game/lang/<language>.ron`
+    #[serde(skip)]
+    pub tables: HashMap<String, HashMap<String, String>>,
+}
+
+impl Localization {
+    /// Text of the key, the key itself when no table has it
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        [&self.language, &self.fallback]
+            .iter()
+            .filter_map(|language| self.tables.get(*language))
+            .find_map(|table| table.get(key))
+            .map(|s| &s[..])
+            .unwrap_or(key)
+    }
+
+    /// Text of the key with `{name}` replaced by the args
+    pub fn format(&self, key: &str, args: &[(&str, String)]) -> String {
+        VariableSystem::interpolate_with(self.get(key), |name| {
+            args.iter()
+                .find(|(arg, _)| *arg == name)
+                .map(|(_, value)| value.clone())
+        })
+    }
+
+    /// Languages with a table, sorted
+    pub fn languages(&self) -> Vec<&String> {
+        let mut languages: Vec<_> = self.tables.keys().collect();
+        languages.sort();
+        languages
+    }
+
+    /// Switch to a language with a table
+    pub fn set_language(&mut self, language: &str) -> bool {
+        if self.tables.contains_key(language) {
+            self.language = String::from(language);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Keys with no text, by language. Keys are the ones given and the ones of
+    /// the fallback table.
+    pub fn missing(&self, keys: &BTreeSet<String>) -> BTreeMap<String, Vec<String>> {
+        let mut keys = keys.clone();
+        if let Some(table) = self.tables.get(&self.fallback) {
+            keys.extend(table.keys().cloned());
+        }
+        self.tables
+            .iter()
+            .map(|(language, table)| {
+                let missing = keys
+                    .iter()
+                    .filter(|key| !table.contains_key(*key))
+                    .cloned()
+                    .collect();
+                (language.clone(), missing)
+            })
+            .collect()
+    }
+}
+
+/// Keys used by novel scripts
+pub fn script_keys(scripts: &[ScriptKind], keys: &mut BTreeSet<String>) {
+    for s in scripts {
+        match s {
+            ScriptKind::Text(key) => {
+                keys.insert(key.clone());
+            }
+            ScriptKind::Say(speaker, key) => {
+                if let Speaker::Named(name, _) = speaker {
+                    keys.insert(name.clone());
+                }
+                keys.insert(key.clone());
+            }
+            ScriptKind::Select(options) => {
+                for option in options {
+                    keys.insert(option.text.clone());
+                    if let Some((_, reason)) = &option.enabled {
+                        keys.insert(reason.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+        let mut b = 0;
+        while let Some(v) = s.branch(b) {
+            script_keys(v, keys);
+            b += 1;
+        }
+    }
+}
+
+/// Keys used by actions, items, characters, quests, player classes and races
+pub fn data_keys<'a>(
+    actions: impl Iterator<Item = &'a Action>,
+    items: impl Iterator<Item = &'a Item>,
+    characters: impl Iterator<Item = &'a Character>,
+    character_templates: impl Iterator<Item = &'a CharacterTemplate>,
+    quests: impl Iterator<Item = &'a Quest>,
+    player_classes: impl Iterator<Item = &'a PlayerClass>,
+    keys: &mut BTreeSet<String>,
+) {
+    for a in actions {
+        keys.insert(a.label.clone());
+        keys.insert(a.description.clone());
+    }
+    for i in items {
+        keys.insert(i.name.clone());
+        keys.insert(i.description.clone());
+    }
+    keys.extend(characters.map(|c| c.name.clone()));
+    keys.extend(character_templates.map(|t| t.name.clone()));
+    for q in quests {
+        keys.insert(q.name.clone());
+        keys.insert(q.description.clone());
+        for stage in &q.stages {
+            keys.insert(stage.description.clone());
+            keys.extend(stage.objectives.iter().map(|o| o.description.clone()));
+        }
+    }
+    for c in player_classes {
+        keys.insert(c.name.clone());
+        keys.insert(c.description.clone());
+    }
+    keys.extend(Race::ALL.iter().map(|r| String::from(r.key())));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing;
+    use super::*;
+
+    #[test]
+    fn shipped_languages_are_complete() {
+        let game = testing::game();
+        let mut keys = BTreeSet::new();
+        data_keys(
+            game.actions.values(),
+            game.items.values(),
+            game.characters.values(),
+            game.character_templates.values(),
+            game.quests.values(),
+            game.player_classes.values(),
+            &mut keys,
+        );
+        for script in game.novel_system.scripts.values() {
+            script_keys(&script.scripts, &mut keys);
+        }
+        for (language, missing) in game.localization.missing(&keys) {
+            assert!(missing.is_empty(), "{}: {:?}", language, missing);
+        }
+    }
+}
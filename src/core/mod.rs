@@ -1,23 +1,37 @@
 mod action;
+mod analysis;
 mod area;
 mod attribute;
 mod character;
 mod command;
+mod creation;
+mod dialogue;
+mod event;
+mod exploration;
 mod floor;
 mod game;
+mod inventory;
+mod localization;
 mod novel;
 mod position;
+mod quest;
 mod shop;
 mod sprite;
 mod teleportation;
+#[cfg(test)]
+mod testing;
 mod tile_map;
 mod turn;
+mod variable;
 
 pub use action::Action;
 pub use action::ActionKind;
 pub use action::EffectKind;
 pub use action::Target;
 pub use action::TargetKind;
+pub use analysis::analyze_scripts;
+pub use analysis::ScriptIssue;
+pub use analysis::ScriptIssueKind;
 pub use area::Area;
 pub use area::AreaSystem;
 pub use attribute::Attribute;
@@ -26,23 +40,66 @@ pub use character::Character;
 pub use character::CharacterAction;
 pub use character::CharacterCrowdControl;
 pub use character::CharacterItem;
+pub use character::CharacterTemplate;
 pub use character::Controller;
+pub use character::CrowdControlKind;
+pub use character::Dialogue;
+pub use character::EquipmentSlot;
 pub use character::Item;
 pub use character::ItemKind;
 pub use character::Race;
+pub use character::LEVEL_EXPERIENCE;
 pub use command::Command;
 pub use command::CommandData;
 pub use command::CommandState;
+pub use creation::AttributePoints;
+pub use creation::CharacterCreation;
+pub use creation::CreationError;
+pub use creation::PlayerClass;
+pub use creation::MAX_NAME_LEN;
+pub use creation::PLAYER_START;
+pub use dialogue::ParseError;
+pub use event::EventQueue;
+pub use event::GameEvent;
+pub use event::MAX_EVENTS;
+pub use exploration::ExplorationSystem;
 pub use floor::FloorSystem;
+pub use floor::BOSS_MAP_ID;
+pub use floor::BOSS_TALK_ID;
 pub use game::Camera;
+pub use game::CameraFade;
+pub use game::CameraFocus;
+pub use game::CameraShake;
 pub use game::Game;
+pub use inventory::InventoryError;
+pub use inventory::InventorySystem;
+pub use localization::data_keys;
+pub use localization::script_keys;
+pub use localization::Localization;
 pub use novel::Background;
+pub use novel::Choice;
 pub use novel::Condition;
 pub use novel::CurrentScript;
+pub use novel::Line;
+pub use novel::NovelError;
+pub use novel::NovelMode;
 pub use novel::NovelSystem;
 pub use novel::Script;
+pub use novel::ScriptCharacter;
 pub use novel::ScriptKind;
+pub use novel::SelectOption;
+pub use novel::Speaker;
 pub use position::Position;
+pub use quest::ActiveQuest;
+pub use quest::CharacterFilter;
+pub use quest::Objective;
+pub use quest::ObjectiveKind;
+pub use quest::Quest;
+pub use quest::QuestEvent;
+pub use quest::QuestStage;
+pub use quest::QuestSystem;
+pub use quest::Resource;
+pub use quest::Reward;
 pub use shop::CurrentShop;
 pub use shop::ShopSystem;
 pub use sprite::Animation;
@@ -51,9 +108,13 @@ pub use sprite::CharacterSprite;
 pub use sprite::SpriteSequence;
 pub use sprite::SpriteSheet;
 pub use teleportation::Teleportation;
+pub use tile_map::Hazard;
 pub use tile_map::Tile;
 pub use tile_map::TileKind;
 pub use tile_map::TileMap;
+pub use turn::TimelineTurn;
 pub use turn::Turn;
 pub use turn::TurnKind;
 pub use turn::TurnSystem;
+pub use variable::Value;
+pub use variable::VariableSystem;
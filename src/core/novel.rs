@@ -1,12 +1,32 @@
-use super::{CharacterAction, Command, CommandState, Game, ItemKind, Position, Target};
+use super::{
+    Animation, AnimationEffect, CameraFade, CameraShake, Character, CharacterAction, Command,
+    CommandState, Controller, CrowdControlKind, Game, ItemKind, Position, QuestSystem, Target,
+    Value, VariableSystem,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
+/// Lines kept in the backlog
+pub const BACKLOG_SIZE: usize = 500;
+/// Instructions run in a row without waiting before giving up on a loop
+const MAX_STEPS: usize = 10_000;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct NovelSystem {
     pub pause: bool,
     pub scripts: HashMap<i32, Script>,
     pub current: Option<CurrentScript>,
+    /// Options picked so far, by script id
+    pub selected: HashMap<i32, HashSet<String>>,
+    /// Lines of the finished conversations, oldest first
+    pub backlog: Vec<Line>,
+    /// Paths of the lines already shown, by script id
+    pub read: HashMap<i32, HashSet<Vec<usize>>>,
+    pub mode: NovelMode,
+    /// Seconds a line is shown in auto mode
+    pub auto_delay: f32,
+    /// Characters talked to before, by initiator
+    pub met: HashMap<i32, HashSet<i32>>,
 }
 
 impl NovelSystem {
@@ -40,9 +60,113 @@ impl NovelSystem {
                         NovelSystem::run(game);
                     }
                 }
+                return;
+            }
+        }
+        // Timed wait
+        if let Some(current) = &mut game.novel_system.current {
+            if let Some(animation) = &mut current.animation {
+                animation.delta += delta;
+                if let Some(id) = animation.sprite_animation_id {
+                    if let Some(effect) = game.sprite_animations.get_mut(&id) {
+                        effect.percentage = (animation.delta / animation.duration).min(1.0);
+                    }
+                }
+                if animation.delta >= animation.duration {
+                    NovelSystem::run(game);
+                }
+                return;
+            }
+        }
+        // Waiting on a line
+        if let Some(current) = &mut game.novel_system.current {
+            if current.select.is_some() || current.waiting_select_ability {
+                return;
+            }
+            current.elapsed += delta;
+            let next = match game.novel_system.mode {
+                NovelMode::Normal => false,
+                NovelMode::Skip => {
+                    // Stop at the first unread line
+                    if !current.read {
+                        game.novel_system.mode = NovelMode::Normal;
+                    }
+                    current.read
+                }
+                NovelMode::Auto => {
+                    current.page_done && current.elapsed >= game.novel_system.auto_delay
+                }
+            };
+            if next {
+                NovelSystem::run(game);
             }
         }
     }
+
+    /// Go to the next instruction after `duration` seconds
+    fn wait(game: &mut Game, duration: f32, sprite_animation_id: Option<i32>) {
+        if let Some(current) = &mut game.novel_system.current {
+            if let Some(i) = current.p.last_mut() {
+                *i += 1;
+            }
+            current.animation = Some(ScriptAnimation {
+                delta: 0.0,
+                duration,
+                sprite_animation_id,
+            });
+        }
+    }
+
+    /// Show a line of the current script and mark it read
+    fn show(game: &mut Game, line: Line) {
+        if let Some(current) = &mut game.novel_system.current {
+            let read = game.novel_system.read.entry(current.id).or_default();
+            current.read = !read.insert(current.p.clone());
+            current.elapsed = 0.0;
+            current.page_done = false;
+            current.history.push(line);
+            if let Some(i) = current.p.last_mut() {
+                *i += 1;
+            }
+        }
+    }
+    /// Localize the key then replace `{name}` with a story variable, and
+    /// `{who.field}` with a field of a character, `who` being `initiator`,
+    /// `target` or a character id and `field` one of name, gold, wood, level,
+    /// experience, hp and mp
+    pub fn interpolate(game: &Game, key: &str) -> String {
+        let text = game.localization.get(key);
+        let (character_id, target_id) = game
+            .novel_system
+            .current
+            .as_ref()
+            .map(|current| (current.character_id, current.target_id))
+            .unwrap_or_default();
+        VariableSystem::interpolate_with(text, |name| {
+            if let Some(value) = game.variable_system.get(name) {
+                return Some(value.to_string());
+            }
+            let dot = name.rfind('.')?;
+            let id = match &name[..dot] {
+                "initiator" => character_id,
+                "target" => target_id,
+                id => id.parse().ok()?,
+            };
+            let c = game.characters.get(&id)?;
+            let value = match &name[dot + 1..] {
+                "name" => String::from(game.localization.get(&c.name)),
+                "gold" => c.gold.to_string(),
+                "wood" => c.wood.to_string(),
+                "level" => c.level.to_string(),
+                "experience" => c.experience.to_string(),
+                "hp" => c.hp.current().to_string(),
+                "mp" => c.mp.current().to_string(),
+                _ => return None,
+            };
+            Some(value)
+        })
+    }
+
     pub fn start(game: &mut Game, id: i32, character_id: i32, target_id: i32) {
         game.novel_system.current = Some(CurrentScript {
             id,
@@ -55,11 +179,36 @@ impl NovelSystem {
             command_id: None,
             waiting_select_ability: false,
             background: None,
+            calls: Default::default(),
+            read: false,
+            elapsed: 0.0,
+            page_done: false,
+            animation: None,
         });
         NovelSystem::run(game);
     }
 
     pub fn run(game: &mut Game) {
+        // Clear timed wait, finishing the camera effects
+        if let Some(animation) = game
+            .novel_system
+            .current
+            .as_mut()
+            .and_then(|c| c.animation.take())
+        {
+            if let Some(id) = animation.sprite_animation_id {
+                game.sprite_animations.remove(&id);
+            }
+            let camera = &mut game.camera;
+            if let Some(focus) = &mut camera.focus {
+                focus.elapsed = focus.duration;
+            }
+            if let Some(fade) = &mut camera.fade {
+                fade.elapsed = fade.duration;
+            }
+            camera.shake = None;
+            camera.update(0.0);
+        }
         // Clear command
         if let Some(command_id) = game
             .novel_system
@@ -86,226 +235,579 @@ impl NovelSystem {
                 }
             }
         }
+        let mut steps = 0;
+        while NovelSystem::step(game) {
+            steps += 1;
+            if steps >= MAX_STEPS {
+                let id = game.novel_system.current.as_ref().map(|c| c.id);
+                log::error!(
+                    "Novel script error: {:?}",
+                    NovelError::Loop(id.unwrap_or(0))
+                );
+                NovelSystem::end(game);
+                break;
+            }
+        }
+    }
+
+    /// Run the instruction at the current position, true when the next one
+    /// runs right away
+    fn step(game: &mut Game) -> bool {
+        let (id, p) = match &mut game.novel_system.current {
+            Some(current) => {
+                // Clear select
+                current.select = None;
+                (current.id, current.p.clone())
+            }
+            None => return false,
+        };
+        if p.is_empty() {
+            // Return from a called script
+            if let Some(current) = &mut game.novel_system.current {
+                if let Some((id, p)) = current.calls.pop() {
+                    current.id = id;
+                    current.p = p;
+                    return true;
+                }
+            }
+            NovelSystem::end(game);
+            return false;
+        }
+        let s = match game.novel_system.scripts.get(&id) {
+            Some(script) => NovelSystem::resolve(&script.scripts, &p),
+            None => Err(NovelError::Script(id)),
+        };
+        let s = match s {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Novel script error: {:?}", e);
+                NovelSystem::end(game);
+                return false;
+            }
+        };
         if let Some(current) = &mut game.novel_system.current {
-            // Clear select
-            current.select = None;
-            // Run
-            if let Some(script) = game.novel_system.scripts.get(&current.id) {
-                if current.p.is_empty() {
-                    game.novel_system.current = None;
-                    game.turn_system.pause = false;
-                } else {
-                    let mut c = script.scripts.get(current.p[0]);
-                    let mut i = 1;
-                    while i < current.p.len() {
-                        if let Some(s) = c {
-                            match s {
-                                ScriptKind::Text(_) => panic!(),
-                                ScriptKind::If(_, v) => {
-                                    c = v.get(current.p[i]);
-                                }
-                                ScriptKind::IfElse(_, _, _) => panic!(),
-                                ScriptKind::Select(v) => {
-                                    if i + 1 < current.p.len() {
-                                        c = v
-                                            .get(current.p[i])
-                                            .and_then(|(_, v)| v.get(current.p[i + 1]));
-                                    } else {
-                                        c = None;
-                                    }
-                                    i += 1;
-                                }
-                                ScriptKind::Shop => panic!(),
-                                ScriptKind::Surrender => panic!(),
-                                ScriptKind::Restart => panic!(),
-                                ScriptKind::CharacterMove(_, _, _) => panic!(),
-                                ScriptKind::CharacterChangePosition(_, _, _, _) => panic!(),
-                                ScriptKind::SelectAbility(v) => {
-                                    c = v.get(current.p[i]);
-                                }
-                                ScriptKind::ForgetSkill => panic!(),
-                                ScriptKind::Background(_) => panic!(),
-                                ScriptKind::PracticeSkill => panic!(),
+            if let Some(s) = s {
+                match s {
+                    ScriptKind::Text(s) => {
+                        let line = Line {
+                            speaker: None,
+                            portrait: None,
+                            text: NovelSystem::interpolate(game, s),
+                            choice: false,
+                        };
+                        NovelSystem::show(game, line);
+                    }
+                    ScriptKind::Say(speaker, s) => {
+                        let (speaker, portrait) = speaker.resolve(game);
+                        let line = Line {
+                            speaker: Some(NovelSystem::interpolate(game, &speaker)),
+                            portrait,
+                            text: NovelSystem::interpolate(game, s),
+                            choice: false,
+                        };
+                        NovelSystem::show(game, line);
+                    }
+                    ScriptKind::If(condition, _) => {
+                        let is_met = Condition::is_met(game, condition);
+                        if let Some(current) = &mut game.novel_system.current {
+                            if is_met {
+                                current.p.push(0);
+                                current.p.push(0);
+                            } else if let Some(i) = current.p.last_mut() {
+                                *i += 1;
                             }
                         }
-                        i += 1;
+                        return true;
                     }
-
-                    if let Some(s) = c {
-                        match s {
-                            ScriptKind::Text(s) => {
-                                current.history.push(s.clone());
+                    ScriptKind::IfElse(condition, _, _) => {
+                        let branch = if Condition::is_met(game, condition) {
+                            0
+                        } else {
+                            1
+                        };
+                        if let Some(current) = &mut game.novel_system.current {
+                            current.p.push(branch);
+                            current.p.push(0);
+                        }
+                        return true;
+                    }
+                    ScriptKind::Select(v) => {
+                        let choices: Vec<Choice> = v
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, option)| match &option.visible {
+                                Some(condition) => Condition::is_met(game, condition),
+                                None => true,
+                            })
+                            .map(|(index, option)| Choice {
+                                index,
+                                text: NovelSystem::interpolate(game, &option.text),
+                                disabled: match &option.enabled {
+                                    Some((condition, reason)) => {
+                                        if Condition::is_met(game, condition) {
+                                            None
+                                        } else {
+                                            Some(NovelSystem::interpolate(game, reason))
+                                        }
+                                    }
+                                    None => None,
+                                },
+                            })
+                            .collect();
+                        if let Some(current) = &mut game.novel_system.current {
+                            if choices.is_empty() {
+                                // Nothing to pick, skip the select
                                 if let Some(i) = current.p.last_mut() {
                                     *i += 1;
                                 }
+                                return true;
+                            } else {
+                                current.select = Some(choices);
                             }
-                            ScriptKind::If(condition, _) => {
-                                if Condition::is_met(game, condition) {
-                                    if let Some(current) = &mut game.novel_system.current {
-                                        current.p.push(0);
-                                    }
-                                }
-                                NovelSystem::run(game);
-                            }
-                            ScriptKind::IfElse(_, _, _) => {}
-                            ScriptKind::Select(v) => {
-                                current.select =
-                                    Some(v.iter().map(|(s, _)| String::from(s)).collect());
+                        }
+                    }
+                    ScriptKind::Shop => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let character_id = current.character_id;
+                        let shop_character_id = current.target_id;
+                        game.open_shop(character_id, shop_character_id);
+                    }
+                    ScriptKind::Surrender => {
+                        let character_id = current.character_id;
+                        let target_id = current.target_id;
+                        Character::defeat(game, target_id, Some(character_id));
+                        NovelSystem::end(game);
+                    }
+                    ScriptKind::Label(_) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        return true;
+                    }
+                    ScriptKind::Goto(label) => {
+                        let p = game
+                            .novel_system
+                            .scripts
+                            .get(&current.id)
+                            .and_then(|script| script.find_label(label));
+                        match p {
+                            Some(p) => {
+                                current.p = p;
+                                return true;
                             }
-                            ScriptKind::Shop => {
-                                if let Some(i) = current.p.last_mut() {
-                                    *i += 1;
-                                }
-                                let character_id = current.character_id;
-                                let shop_character_id = current.target_id;
-                                game.open_shop(character_id, shop_character_id);
+                            None => {
+                                log::error!(
+                                    "Novel script error: {:?}",
+                                    NovelError::Label(label.clone())
+                                );
+                                NovelSystem::end(game);
                             }
-                            ScriptKind::Surrender => {}
-                            ScriptKind::Restart => {
-                                current.p = vec![0];
-                                NovelSystem::run(game);
+                        }
+                    }
+                    ScriptKind::Call(id) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let p = std::mem::replace(&mut current.p, vec![0]);
+                        current.calls.push((current.id, p));
+                        current.id = *id;
+                        return true;
+                    }
+                    ScriptKind::End => {
+                        NovelSystem::end(game);
+                    }
+                    ScriptKind::Restart => {
+                        current.p = vec![0];
+                        return true;
+                    }
+                    ScriptKind::CharacterMove(c, x, y) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let character_id = c.character_id(current.character_id, current.target_id);
+                        // game.character_move(character_id, *x, *y);
+                        let action_id = game
+                            .characters
+                            .get(&character_id)
+                            .and_then(|c| {
+                                c.character_action_ids
+                                    .iter()
+                                    .filter_map(|id| game.character_actions.get(id))
+                                    .find(|ca| {
+                                        game.actions.get(&ca.action_id).map(|a| &a.name[..])
+                                            == Some("Move")
+                                    })
+                                    .map(|a| a.action_id)
+                            })
+                            .unwrap();
+                        let target = Target::Position(Position {
+                            x: *x,
+                            y: *y,
+                            map_id: game
+                                .characters
+                                .get(&character_id)
+                                .map(|c| c.position.map_id)
+                                .unwrap(),
+                        });
+                        let command = Command::new(game, character_id, action_id, target).unwrap();
+                        if let Some(current) = &mut game.novel_system.current {
+                            let id = 1 + *game.commands.keys().max().unwrap_or(&0);
+                            game.commands.insert(id, command);
+                            current.command_id = Some(id);
+                        }
+                    }
+                    ScriptKind::CharacterChangePosition(c, x, y, map_id) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let character_id = c.character_id(current.character_id, current.target_id);
+                        game.character_change_position(character_id, *x, *y, *map_id);
+                        return true;
+                    }
+                    ScriptKind::SelectAbility(_) => {
+                        current.waiting_select_ability = true;
+                    }
+                    ScriptKind::ForgetSkill => {
+                        if let Some(index) = current.select_ability_character_action_index {
+                            if let Some(character) = game.characters.get_mut(&current.character_id)
+                            {
+                                character.character_action_ids.remove(index);
                             }
-                            ScriptKind::CharacterMove(c, x, y) => {
-                                if let Some(i) = current.p.last_mut() {
-                                    *i += 1;
-                                }
-                                let character_id = match c {
-                                    ScriptCharacter::Initiator => current.character_id,
-                                    ScriptCharacter::Target => current.target_id,
-                                };
-                                // game.character_move(character_id, *x, *y);
-                                let action_id = game
-                                    .characters
-                                    .get(&character_id)
-                                    .and_then(|c| {
-                                        c.character_action_ids
+                        }
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        return true;
+                    }
+                    ScriptKind::Background(background) => {
+                        current.background = Some(Background::clone(background));
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        return true;
+                    }
+                    ScriptKind::PracticeSkill => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+
+                        let character_id = current.character_id;
+                        let action_ids = game.characters.get(&character_id).map(|c| {
+                            c.items
+                                .iter()
+                                .map(|ci| ci.item_id)
+                                .map(|id| game.items.get(&id))
+                                .flatten()
+                                .filter_map(|item| match item.item_kind {
+                                    ItemKind::ActionBook(id) => {
+                                        if c.character_action_ids
                                             .iter()
                                             .filter_map(|id| game.character_actions.get(id))
-                                            .find(|ca| {
-                                                game.actions.get(&ca.action_id).map(|a| &a.name[..])
-                                                    == Some("Move")
-                                            })
-                                            .map(|a| a.action_id)
-                                    })
-                                    .unwrap();
-                                let target = Target::Position(Position {
-                                    x: *x,
-                                    y: *y,
-                                    map_id: game
-                                        .characters
-                                        .get(&character_id)
-                                        .map(|c| c.position.map_id)
-                                        .unwrap(),
-                                });
-                                let command =
-                                    Command::new(game, character_id, action_id, target).unwrap();
-                                if let Some(current) = &mut game.novel_system.current {
-                                    let id = 1 + *game.commands.keys().max().unwrap_or(&0);
-                                    game.commands.insert(id, command);
-                                    current.command_id = Some(id);
-                                }
-                            }
-                            ScriptKind::CharacterChangePosition(c, x, y, map_id) => {
-                                if let Some(i) = current.p.last_mut() {
-                                    *i += 1;
-                                }
-                                let character_id = match c {
-                                    ScriptCharacter::Initiator => current.character_id,
-                                    ScriptCharacter::Target => current.target_id,
-                                };
-                                game.character_change_position(character_id, *x, *y, *map_id);
-                                NovelSystem::run(game);
-                            }
-                            ScriptKind::SelectAbility(_) => {
-                                current.waiting_select_ability = true;
-                            }
-                            ScriptKind::ForgetSkill => {
-                                if let Some(index) = current.select_ability_character_action_index {
-                                    if let Some(character) =
-                                        game.characters.get_mut(&current.character_id)
-                                    {
-                                        character.character_action_ids.remove(index);
+                                            .any(|a| a.action_id == id)
+                                        {
+                                            None
+                                        } else {
+                                            Some(id)
+                                        }
                                     }
+                                    _ => None,
+                                })
+                                .collect::<HashSet<_>>()
+                                .into_iter()
+                                .collect::<Vec<_>>()
+                        });
+                        if let Some(ids) = action_ids {
+                            for action_id in ids {
+                                let id = 1 + *game.character_actions.keys().max().unwrap_or(&0);
+                                let v = CharacterAction::new(action_id);
+                                game.character_actions.insert(id, v);
+                                if let Some(c) = game.characters.get_mut(&character_id) {
+                                    c.character_action_ids.push(id);
                                 }
-                                if let Some(i) = current.p.last_mut() {
-                                    *i += 1;
-                                }
-                                NovelSystem::run(game);
                             }
-                            ScriptKind::Background(background) => {
-                                current.background = Some(Background::clone(background));
-                                if let Some(i) = current.p.last_mut() {
-                                    *i += 1;
-                                }
-                                NovelSystem::run(game);
-                            }
-                            ScriptKind::PracticeSkill => {
-                                if let Some(i) = current.p.last_mut() {
-                                    *i += 1;
-                                }
-
-                                let character_id = current.character_id;
-                                let action_ids = game.characters.get(&character_id).map(|c| {
-                                    c.items
-                                        .iter()
-                                        .map(|ci| ci.item_id)
-                                        .map(|id| game.items.get(&id))
-                                        .flatten()
-                                        .filter_map(|item| match item.item_kind {
-                                            ItemKind::ActionBook(id) => {
-                                                if c.character_action_ids
-                                                    .iter()
-                                                    .filter_map(|id| game.character_actions.get(id))
-                                                    .any(|a| a.action_id == id)
-                                                {
-                                                    None
-                                                } else {
-                                                    Some(id)
-                                                }
-                                            }
-                                            _ => None,
+                            if let Some(c) = game.characters.get_mut(&character_id) {
+                                let items = &game.items;
+                                c.items.retain(|ci| {
+                                    items
+                                        .get(&ci.item_id)
+                                        .map(|item| match item.item_kind {
+                                            ItemKind::ActionBook(_) => false,
+                                            _ => true,
                                         })
-                                        .collect::<HashSet<_>>()
-                                        .into_iter()
-                                        .collect::<Vec<_>>()
+                                        .unwrap_or(true)
                                 });
-                                if let Some(ids) = action_ids {
-                                    for action_id in ids {
-                                        let id =
-                                            1 + *game.character_actions.keys().max().unwrap_or(&0);
-                                        let v = CharacterAction::new(action_id);
-                                        game.character_actions.insert(id, v);
-                                        if let Some(c) = game.characters.get_mut(&character_id) {
-                                            c.character_action_ids.push(id);
-                                        }
-                                    }
-                                    if let Some(c) = game.characters.get_mut(&character_id) {
-                                        let items = &game.items;
-                                        c.items.retain(|ci| {
-                                            items
-                                                .get(&ci.item_id)
-                                                .map(|item| match item.item_kind {
-                                                    ItemKind::ActionBook(_) => false,
-                                                    _ => true,
-                                                })
-                                                .unwrap_or(true)
-                                        });
-                                    }
-                                }
-
-                                NovelSystem::run(game);
                             }
                         }
-                    } else {
-                        current.p.pop();
+
+                        return true;
+                    }
+                    ScriptKind::SetVariable(name, value) => {
                         if let Some(i) = current.p.last_mut() {
                             *i += 1;
                         }
-                        NovelSystem::run(game);
+                        game.variable_system.set(name, value.clone());
+                        return true;
+                    }
+                    ScriptKind::AddVariable(name, value) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        game.variable_system.add(name, *value);
+                        return true;
+                    }
+                    ScriptKind::ClearVariable(name) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        game.variable_system.clear(name);
+                        return true;
                     }
+                    ScriptKind::GiveItem(c, item_id, quantity) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let character_id = c.character_id(current.character_id, current.target_id);
+                        Character::gain_item(game, character_id, *item_id, *quantity);
+                        return true;
+                    }
+                    ScriptKind::TakeItem(c, item_id, quantity) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let character_id = c.character_id(current.character_id, current.target_id);
+                        if let Some(c) = game.characters.get_mut(&character_id) {
+                            c.take_item(*item_id, *quantity);
+                        }
+                        return true;
+                    }
+                    ScriptKind::AddGold(c, amount) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let character_id = c.character_id(current.character_id, current.target_id);
+                        if let Some(c) = game.characters.get_mut(&character_id) {
+                            c.gold = (c.gold + amount).max(0);
+                        }
+                        return true;
+                    }
+                    ScriptKind::AddWood(c, amount) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let character_id = c.character_id(current.character_id, current.target_id);
+                        if let Some(c) = game.characters.get_mut(&character_id) {
+                            c.wood = (c.wood + amount).max(0);
+                        }
+                        return true;
+                    }
+                    ScriptKind::Heal(c, amount) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let character_id = c.character_id(current.character_id, current.target_id);
+                        let amount = *amount;
+                        if game.characters.contains_key(&character_id) {
+                            Character::heal_hp(game, amount, character_id, None);
+                        }
+                        return true;
+                    }
+                    ScriptKind::Damage(c, amount) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let character_id = c.character_id(current.character_id, current.target_id);
+                        let amount = *amount;
+                        if game.characters.contains_key(&character_id) {
+                            Character::take_damage(game, amount, character_id, None);
+                        }
+                        return true;
+                    }
+                    ScriptKind::ApplyStatus(c, kind, duration) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let character_id = c.character_id(current.character_id, current.target_id);
+                        Character::apply_status(game, *kind, *duration, character_id, None);
+                        return true;
+                    }
+                    ScriptKind::GainExp(c, exp) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let character_id = c.character_id(current.character_id, current.target_id);
+                        Character::give_exp(game, character_id, *exp);
+                        return true;
+                    }
+                    ScriptKind::SetController(c, controller) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let character_id = c.character_id(current.character_id, current.target_id);
+                        if let Some(c) = game.characters.get_mut(&character_id) {
+                            c.controller = *controller;
+                        }
+                        return true;
+                    }
+                    ScriptKind::CameraFocus(c, duration) => {
+                        let character_id = c.character_id(current.character_id, current.target_id);
+                        let duration = *duration;
+                        if let Some(position) =
+                            game.characters.get(&character_id).map(|c| c.position)
+                        {
+                            game.camera_pan(position, duration, false);
+                        }
+                        NovelSystem::wait(game, duration, None);
+                    }
+                    ScriptKind::CameraPan(position, duration) => {
+                        let (position, duration) = (*position, *duration);
+                        game.camera_pan(position, duration, false);
+                        NovelSystem::wait(game, duration, None);
+                    }
+                    ScriptKind::CameraReset(duration) => {
+                        let duration = *duration;
+                        let position = game
+                            .characters
+                            .get(&game.camera.character_id)
+                            .map(|c| c.position);
+                        match position {
+                            Some(position) => game.camera_pan(position, duration, true),
+                            None => game.camera.focus = None,
+                        }
+                        NovelSystem::wait(game, duration, None);
+                    }
+                    ScriptKind::Shake(intensity, duration) => {
+                        let duration = *duration;
+                        game.camera.shake = Some(CameraShake {
+                            intensity: *intensity,
+                            elapsed: 0.0,
+                            duration,
+                        });
+                        NovelSystem::wait(game, duration, None);
+                    }
+                    ScriptKind::Fade(r, g, b, a, duration) => {
+                        let duration = *duration;
+                        let from = game.camera.fade.as_ref().map(|f| f.alpha()).unwrap_or(0);
+                        game.camera.fade = Some(CameraFade {
+                            color: (*r, *g, *b),
+                            from,
+                            to: *a,
+                            elapsed: 0.0,
+                            duration,
+                        });
+                        NovelSystem::wait(game, duration, None);
+                    }
+                    ScriptKind::Wait(duration) => {
+                        let duration = *duration;
+                        NovelSystem::wait(game, duration, None);
+                    }
+                    ScriptKind::PlayAnimation(animation, position, duration) => {
+                        let id = 1 + *game.sprite_animations.keys().max().unwrap_or(&0);
+                        let effect = AnimationEffect {
+                            id,
+                            animation: animation.clone(),
+                            percentage: 0.0,
+                            position: *position,
+                        };
+                        let duration = *duration;
+                        game.sprite_animations.insert(id, effect);
+                        NovelSystem::wait(game, duration, Some(id));
+                    }
+                    ScriptKind::Spawn(template_id, position) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let (template_id, position) = (*template_id, *position);
+                        if game.spawn_character(template_id, position).is_none() {
+                            log::error!(
+                                "Cannot spawn character template {} near {:?}",
+                                template_id,
+                                position
+                            );
+                        }
+                        return true;
+                    }
+                    ScriptKind::StartQuest(quest_id) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let (quest_id, character_id) = (*quest_id, current.character_id);
+                        QuestSystem::start(game, quest_id, character_id);
+                        return true;
+                    }
+                    ScriptKind::AdvanceQuest(quest_id) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let quest_id = *quest_id;
+                        QuestSystem::advance(game, quest_id);
+                        return true;
+                    }
+                    ScriptKind::CompleteQuest(quest_id) => {
+                        if let Some(i) = current.p.last_mut() {
+                            *i += 1;
+                        }
+                        let quest_id = *quest_id;
+                        QuestSystem::complete(game, quest_id);
+                        return true;
+                    }
+                }
+            } else {
+                // End of block, back to the instruction containing it
+                let len = current.p.len();
+                current.p.truncate(len.saturating_sub(2));
+                if let Some(i) = current.p.last_mut() {
+                    *i += 1;
                 }
+                return true;
             }
         }
+        false
+    }
+
+    /// Find the instruction at `p`, `None` when `p` points past the end of a block.
+    ///
+    /// `p` is the index in the top level followed by a (branch, index) pair for
+    /// each block entered, see `ScriptKind::branch`.
+    pub fn resolve<'a>(
+        scripts: &'a [ScriptKind],
+        p: &[usize],
+    ) -> Result<Option<&'a ScriptKind>, NovelError> {
+        let mut body = scripts;
+        let mut i = 0;
+        while i < p.len() {
+            let s = body.get(p[i]);
+            if i + 1 == p.len() {
+                return Ok(s);
+            }
+            match s.and_then(|s| p.get(i + 1).and_then(|b| s.branch(*b))) {
+                Some(v) => body = v,
+                None => break,
+            }
+            i += 2;
+        }
+        Err(NovelError::Path(p.to_vec()))
+    }
+
+    pub fn end(game: &mut Game) {
+        if let Some(current) = game.novel_system.current.take() {
+            game.novel_system
+                .met
+                .entry(current.character_id)
+                .or_default()
+                .insert(current.target_id);
+            let backlog = &mut game.novel_system.backlog;
+            backlog.extend(current.history);
+            let len = backlog.len();
+            backlog.drain(..len.saturating_sub(BACKLOG_SIZE));
+        }
+        // The camera follows the character again, without fade or shake
+        let camera = &mut game.camera;
+        camera.focus = None;
+        camera.fade = None;
+        camera.shake = None;
+        game.turn_system.pause = false;
     }
 
     pub fn next(game: &mut Game) {
@@ -317,9 +819,39 @@ impl NovelSystem {
         }
     }
 
+    /// Pick the `i`th shown choice, disabled choices are ignored
     pub fn select(game: &mut Game, i: usize) {
         if let Some(current) = &mut game.novel_system.current {
-            current.p.push(i);
+            let index = match current.select.as_ref().and_then(|s| s.get(i)) {
+                Some(choice) if choice.disabled.is_none() => {
+                    current.history.push(Line {
+                        speaker: None,
+                        portrait: None,
+                        text: format!("> {}", choice.text),
+                        choice: true,
+                    });
+                    choice.index
+                }
+                _ => return,
+            };
+            let text = game
+                .novel_system
+                .scripts
+                .get(&current.id)
+                .and_then(|script| NovelSystem::resolve(&script.scripts, &current.p).ok())
+                .flatten()
+                .and_then(|s| match s {
+                    ScriptKind::Select(v) => v.get(index).map(|option| option.text.clone()),
+                    _ => None,
+                });
+            if let Some(text) = text {
+                game.novel_system
+                    .selected
+                    .entry(current.id)
+                    .or_default()
+                    .insert(text);
+            }
+            current.p.push(index);
             current.p.push(0);
             NovelSystem::run(game);
         }
@@ -330,29 +862,96 @@ impl NovelSystem {
             current.waiting_select_ability = false;
             current.select_ability_character_action_index = Some(index);
             current.p.push(0);
+            current.p.push(0);
             NovelSystem::run(game);
         }
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub enum NovelError {
+    /// No script with the id
+    Script(i32),
+    /// Path does not lead to an instruction
+    Path(Vec<usize>),
+    /// No label with the name in the script
+    Label(String),
+    /// Too many instructions in a row without waiting, in the script with the id
+    Loop(i32),
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CurrentScript {
     pub id: i32,
     pub character_id: i32,
     pub target_id: i32,
     pub p: Vec<usize>,
-    pub history: Vec<String>,
-    pub select: Option<Vec<String>>,
+    pub history: Vec<Line>,
+    pub select: Option<Vec<Choice>>,
     pub select_ability_character_action_index: Option<usize>,
     pub waiting_select_ability: bool,
-    // pub animation: Option<ScriptAnimation>,
+    /// Timed wait, the script goes on once it ends
+    pub animation: Option<ScriptAnimation>,
     pub command_id: Option<i32>,
     pub background: Option<Background>,
+    /// Where to return when a called script ends, (id, p)
+    pub calls: Vec<(i32, Vec<usize>)>,
+    /// The shown line had been read before
+    pub read: bool,
+    /// Seconds the line has been shown
+    pub elapsed: f32,
+    /// The last page of the line is shown, set by the scene
+    #[serde(default)]
+    pub page_done: bool,
+}
+
+impl CurrentScript {
+    /// Index in the history and line of the text box, picked choices skipped
+    pub fn line(&self) -> Option<(usize, &Line)> {
+        self.history
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, l)| !l.choice)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Copy, Clone)]
+pub enum NovelMode {
+    Normal,
+    /// Go through lines already read
+    Skip,
+    /// Go to the next line after `auto_delay`
+    Auto,
+}
+
+/// An option of a select as shown to the player
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Choice {
+    /// Index of the option in the `Select`, hidden options are not shown
+    pub index: usize,
+    pub text: String,
+    /// Reason the option cannot be picked
+    pub disabled: Option<String>,
+}
+
+/// A shown text, with the speaker's name and portrait if someone says it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Line {
+    pub speaker: Option<String>,
+    pub portrait: Option<String>,
+    pub text: String,
+    /// A picked choice, only listed in the backlog
+    #[serde(default)]
+    pub choice: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ScriptAnimation {
     pub delta: f32,
+    pub duration: f32,
+    /// Effect removed when the wait ends
+    pub sprite_animation_id: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -364,11 +963,20 @@ pub struct Script {
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ScriptKind {
     Text(String),
+    /// Text said by someone
+    Say(Speaker, String),
     Background(Background),
     If(Condition, Vec<ScriptKind>),
     IfElse(Condition, Vec<ScriptKind>, Vec<ScriptKind>),
-    Select(Vec<(String, Vec<ScriptKind>)>),
+    Select(Vec<SelectOption>),
     Restart,
+    Label(String),
+    /// Jump to a label in the same script
+    Goto(String),
+    /// Run another script then continue after this instruction
+    Call(i32),
+    /// End the conversation, even inside a called script
+    End,
     Shop,
     Surrender,
     CharacterMove(ScriptCharacter, i32, i32),
@@ -376,21 +984,248 @@ pub enum ScriptKind {
     SelectAbility(Vec<ScriptKind>),
     ForgetSkill,
     PracticeSkill,
+    SetVariable(String, Value),
+    /// AddVariable(name, amount), for int variables
+    AddVariable(String, i32),
+    ClearVariable(String),
+    /// GiveItem(character, item_id, quantity)
+    GiveItem(ScriptCharacter, i32, i32),
+    /// TakeItem(character, item_id, quantity), takes what the character has
+    TakeItem(ScriptCharacter, i32, i32),
+    /// Negative amounts take, down to 0
+    AddGold(ScriptCharacter, i32),
+    AddWood(ScriptCharacter, i32),
+    Heal(ScriptCharacter, i32),
+    Damage(ScriptCharacter, i32),
+    ApplyStatus(ScriptCharacter, CrowdControlKind, i32),
+    GainExp(ScriptCharacter, i32),
+    /// For example wake a waiting boss with `Boss(waiting: false)`
+    SetController(ScriptCharacter, Controller),
+    /// Spawn(template_id, position), see character_templates.ron, on the free
+    /// tile nearest to the position
+    Spawn(i32, Position),
+    /// Pan the camera to the character in seconds, and wait for it
+    CameraFocus(ScriptCharacter, f32),
+    CameraPan(Position, f32),
+    /// Pan back to the camera character and follow it again
+    CameraReset(f32),
+    /// Shake(intensity in tiles, seconds)
+    Shake(f32, f32),
+    /// Fade(r, g, b, a, seconds) from the current fade, fading to a = 0 clears it
+    Fade(u8, u8, u8, u8, f32),
+    Wait(f32),
+    /// PlayAnimation(animation, position, seconds)
+    PlayAnimation(Animation, Position, f32),
+    /// Start the quest for the initiator
+    StartQuest(i32),
+    /// Go to the next stage of the quest, completing it after the last one
+    AdvanceQuest(i32),
+    /// Complete the quest at any stage and give the rewards
+    CompleteQuest(i32),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub enum Condition {}
+pub struct SelectOption {
+    pub text: String,
+    /// Hidden unless met
+    #[serde(default)]
+    pub visible: Option<Condition>,
+    /// Shown but cannot be picked unless met, with the reason
+    #[serde(default)]
+    pub enabled: Option<(Condition, String)>,
+    pub scripts: Vec<ScriptKind>,
+}
+
+impl Script {
+    /// Path of the label, searching every block
+    pub fn find_label(&self, label: &str) -> Option<Vec<usize>> {
+        Script::find_label_in(&self.scripts, label)
+    }
+
+    fn find_label_in(scripts: &[ScriptKind], label: &str) -> Option<Vec<usize>> {
+        for (i, s) in scripts.iter().enumerate() {
+            if let ScriptKind::Label(name) = s {
+                if name == label {
+                    return Some(vec![i]);
+                }
+            }
+            let mut b = 0;
+            while let Some(v) = s.branch(b) {
+                if let Some(p) = Script::find_label_in(v, label) {
+                    return Some([vec![i, b], p].concat());
+                }
+                b += 1;
+            }
+        }
+        None
+    }
+}
+
+impl ScriptKind {
+    /// Body of a branch for instructions containing blocks.
+    /// `If` and `SelectAbility` have a single branch 0, `IfElse` has 0 and 1
+    /// and `Select` has one branch per option.
+    pub fn branch(&self, i: usize) -> Option<&Vec<ScriptKind>> {
+        match self {
+            ScriptKind::If(_, v) | ScriptKind::SelectAbility(v) if i == 0 => Some(v),
+            ScriptKind::IfElse(_, v, _) if i == 0 => Some(v),
+            ScriptKind::IfElse(_, _, v) if i == 1 => Some(v),
+            ScriptKind::Select(v) => v.get(i).map(|option| &option.scripts),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum Condition {
+    /// HasItem(character, item_id, at least quantity)
+    HasItem(ScriptCharacter, i32, i32),
+    /// Gold(character, at least)
+    Gold(ScriptCharacter, i32),
+    /// Wood(character, at least)
+    Wood(ScriptCharacter, i32),
+    /// Level(character, at least)
+    Level(ScriptCharacter, i32),
+    /// Current floor is exactly
+    Floor(i32),
+    /// Current floor is at least
+    FloorReached(i32),
+    FlagSet(String),
+    FlagEquals(String, Value),
+    Defeated(ScriptCharacter),
+    /// The initiator talked with the character in an earlier conversation
+    Met(ScriptCharacter),
+    QuestActive(i32),
+    /// QuestStage(quest_id, stage), the quest is active at the stage
+    QuestStage(i32, usize),
+    QuestCompleted(i32),
+    /// Selected(script_id, option), the option was ever picked in that script
+    Selected(i32, String),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    Not(Box<Condition>),
+}
 
 impl Condition {
+    /// Check against the characters of the running script
     pub fn is_met(game: &Game, condition: &Condition) -> bool {
-        false
+        match &game.novel_system.current {
+            Some(current) => {
+                Condition::check(game, condition, current.character_id, current.target_id)
+            }
+            None => false,
+        }
+    }
+
+    pub fn check(game: &Game, condition: &Condition, character_id: i32, target_id: i32) -> bool {
+        let character = |c: &ScriptCharacter| {
+            game.characters
+                .get(&c.character_id(character_id, target_id))
+        };
+        match condition {
+            Condition::HasItem(c, item_id, quantity) => character(c)
+                .map(|c| c.item_count(*item_id) >= *quantity)
+                .unwrap_or(false),
+            Condition::Gold(c, gold) => character(c).map(|c| c.gold >= *gold).unwrap_or(false),
+            Condition::Wood(c, wood) => character(c).map(|c| c.wood >= *wood).unwrap_or(false),
+            Condition::Level(c, level) => character(c).map(|c| c.level >= *level).unwrap_or(false),
+            Condition::Floor(floor) => game
+                .floor_system
+                .current
+                .as_ref()
+                .map(|c| c.floor == *floor)
+                .unwrap_or(false),
+            Condition::FloorReached(floor) => game
+                .floor_system
+                .current
+                .as_ref()
+                .map(|c| c.floor >= *floor)
+                .unwrap_or(false),
+            Condition::FlagSet(name) => game.variable_system.is_set(name),
+            Condition::FlagEquals(name, value) => game.variable_system.get(name) == Some(value),
+            Condition::Defeated(c) => character(c).map(|c| c.defeated).unwrap_or(false),
+            Condition::Met(c) => game
+                .novel_system
+                .met
+                .get(&character_id)
+                .map(|met| met.contains(&c.character_id(character_id, target_id)))
+                .unwrap_or(false),
+            Condition::QuestActive(quest_id) => game.quest_system.active(*quest_id).is_some(),
+            Condition::QuestStage(quest_id, stage) => game
+                .quest_system
+                .active(*quest_id)
+                .map(|q| q.stage == *stage)
+                .unwrap_or(false),
+            Condition::QuestCompleted(quest_id) => game.quest_system.is_completed(*quest_id),
+            Condition::Selected(script_id, option) => game
+                .novel_system
+                .selected
+                .get(script_id)
+                .map(|s| s.contains(option))
+                .unwrap_or(false),
+            Condition::And(v) => v
+                .iter()
+                .all(|c| Condition::check(game, c, character_id, target_id)),
+            Condition::Or(v) => v
+                .iter()
+                .any(|c| Condition::check(game, c, character_id, target_id)),
+            Condition::Not(c) => !Condition::check(game, c, character_id, target_id),
+        }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub enum ScriptCharacter {
     Initiator,
     Target,
+    Character(i32),
+}
+
+impl ScriptCharacter {
+    pub fn character_id(&self, character_id: i32, target_id: i32) -> i32 {
+        match self {
+            ScriptCharacter::Initiator => character_id,
+            ScriptCharacter::Target => target_id,
+            ScriptCharacter::Character(id) => *id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum Speaker {
+    /// Name and portrait of the character
+    Character(ScriptCharacter),
+    /// Named(name, portrait) for speakers who are not characters
+    Named(String, Option<String>),
+}
+
+impl Speaker {
+    /// (name, portrait) of the speaker in the current script
+    pub fn resolve(&self, game: &Game) -> (String, Option<String>) {
+        match self {
+            Speaker::Character(c) => {
+                let (character_id, target_id) = game
+                    .novel_system
+                    .current
+                    .as_ref()
+                    .map(|current| (current.character_id, current.target_id))
+                    .unwrap_or_default();
+                match game
+                    .characters
+                    .get(&c.character_id(character_id, target_id))
+                {
+                    Some(character) => (
+                        character.name.clone(),
+                        game.character_sprites
+                            .get(&character.character_sprite_id)
+                            .and_then(|sprite| sprite.portrait.clone()),
+                    ),
+                    None => (String::new(), None),
+                }
+            }
+            Speaker::Named(name, portrait) => (name.clone(), portrait.clone()),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -399,3 +1234,133 @@ pub enum Background {
     Color(u8, u8, u8, u8),
     Image(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::{testing, GameEvent};
+    use super::*;
+
+    fn start(source: &str) -> Game {
+        let mut game = testing::game();
+        let script = Script::compile(999, "test", source).unwrap();
+        game.novel_system.scripts.insert(999, script);
+        NovelSystem::start(&mut game, 999, 1, 0);
+        game
+    }
+
+    #[test]
+    fn condition_check() {
+        let mut game = testing::game();
+        game.characters.get_mut(&1).unwrap().gold = 20;
+        game.variable_system.set("flag", Value::Bool(true));
+        let check = |game: &Game, source: &str| {
+            let condition: Condition = ron::de::from_str(source).unwrap();
+            Condition::check(game, &condition, 1, 0)
+        };
+        assert!(check(&game, "Gold(Initiator, 20)"));
+        assert!(!check(&game, "Gold(Initiator, 21)"));
+        assert!(!check(&game, "Gold(Target, 20)"));
+        assert!(check(&game, "FlagSet(\"flag\")"));
+        assert!(!check(&game, "FlagSet(\"other\")"));
+        assert!(check(&game, "FlagEquals(\"flag\", Bool(true))"));
+        assert!(!check(&game, "Met(Target)"));
+        game.novel_system.met.entry(1).or_default().insert(0);
+        assert!(check(&game, "Met(Target)"));
+        assert!(check(
+            &game,
+            "And([Gold(Initiator, 1), Not(FlagSet(\"other\"))])"
+        ));
+        assert!(!check(
+            &game,
+            "And([Gold(Initiator, 1), FlagSet(\"other\")])"
+        ));
+        assert!(check(&game, "Or([FlagSet(\"other\"), Gold(Initiator, 1)])"));
+        assert!(!check(&game, "Or([])"));
+    }
+
+    #[test]
+    fn heal_emits_an_event() {
+        let mut game = testing::game();
+        game.characters.get_mut(&1).unwrap().hp.damage = 3;
+        let script = Script::compile(999, "test", "@heal initiator 5\nDone\n").unwrap();
+        game.novel_system.scripts.insert(999, script);
+        NovelSystem::start(&mut game, 999, 1, 0);
+        assert_eq!(game.characters[&1].hp.damage, 0);
+        assert_eq!(
+            game.events.since(0).collect::<Vec<_>>(),
+            vec![&GameEvent::Healed {
+                target_id: 1,
+                source_id: None,
+                amount: 3,
+            }]
+        );
+    }
+
+    #[test]
+    fn camera_released_at_the_end() {
+        let mut game = start("@focus 0 0.5\n@fade 0 0 0 255 0.5\n");
+        NovelSystem::update(&mut game, 1.0);
+        NovelSystem::update(&mut game, 1.0);
+        assert!(game.novel_system.current.is_none());
+        assert!(game.camera.fade.is_none());
+        let player = game.characters[&game.camera.character_id].position;
+        assert_eq!(
+            game.camera_center(),
+            Some((player.map_id, player.x as f32, player.y as f32))
+        );
+    }
+
+    #[test]
+    fn goto_loop_ends() {
+        let game = start("@label top\n@goto top\n");
+        assert!(game.novel_system.current.is_none());
+    }
+
+    #[test]
+    fn restart_over_hidden_select_ends() {
+        let game = start("- Hidden\n    @visible FlagSet(\"never\")\n    Hi\n@restart\n");
+        assert!(game.novel_system.current.is_none());
+    }
+
+    #[test]
+    fn choice_only_in_history() {
+        let mut game = start("Pick one\n- Yes\n    Ok\n- No\n    @wait 1\n");
+        NovelSystem::run(&mut game);
+        NovelSystem::select(&mut game, 1);
+        let current = game.novel_system.current.as_ref().unwrap();
+        let (i, line) = current.line().unwrap();
+        assert_eq!((i, &line.text[..]), (0, "Pick one"));
+        assert!(current.history[1].choice);
+        assert_eq!(current.history[1].text, "> No");
+    }
+
+    #[test]
+    fn auto_waits_for_the_last_page() {
+        let mut game = start("Long line\nNext\n");
+        game.novel_system.mode = NovelMode::Auto;
+        let delay = game.novel_system.auto_delay;
+        NovelSystem::update(&mut game, delay + 1.0);
+        let current = game.novel_system.current.as_mut().unwrap();
+        assert_eq!(current.history.len(), 1);
+
+        // The scene turned to the last page
+        current.page_done = true;
+        current.elapsed = 0.0;
+        NovelSystem::update(&mut game, delay + 1.0);
+        let current = game.novel_system.current.as_ref().unwrap();
+        assert_eq!(current.line().unwrap().1.text, "Next");
+        assert!(!current.page_done);
+    }
+
+    #[test]
+    fn long_run_without_waiting() {
+        let source = "@add count 1\n".repeat(5000) + "Done\n";
+        let game = start(&source);
+        let current = game.novel_system.current.as_ref().unwrap();
+        assert_eq!(current.history.last().unwrap().text, "Done");
+        assert_eq!(
+            game.variable_system.get("count").map(|v| v.to_string()),
+            Some(String::from("5000"))
+        );
+    }
+}
@@ -0,0 +1,358 @@
+use super::{Character, Condition, Controller, Game};
+use serde::{Deserialize, Serialize};
+
+/// Quest definition, see quests.ron. Name and descriptions are localization
+/// keys.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Quest {
+    pub id: i32,
+    pub name: String,
+    pub description: String,
+    pub stages: Vec<QuestStage>,
+    /// Given to the character holding the quest when it is completed
+    pub rewards: Vec<Reward>,
+}
+
+/// Once every objective is done the quest goes to the next stage, a stage
+/// without objectives waits for a script to advance it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuestStage {
+    pub description: String,
+    pub objectives: Vec<Objective>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Objective {
+    pub description: String,
+    pub kind: ObjectiveKind,
+    /// Times the event must happen, or amount to hold for Collect
+    pub count: i32,
+    /// Events only count when met, checked with the character of the event as
+    /// initiator and the other one as target
+    #[serde(default)]
+    pub condition: Option<Condition>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum ObjectiveKind {
+    /// Defeat characters matching, by anyone
+    Defeat(CharacterFilter),
+    /// Hold the amount, counted from what the quest holder has
+    Collect(Resource),
+    /// Quest holder enters the area
+    Reach(i32),
+    /// Quest holder starts a conversation with the character
+    Talk(i32),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum CharacterFilter {
+    Any,
+    Boss,
+    Character(i32),
+    /// Characters with the name key, for example "character.bandit"
+    Name(String),
+}
+
+impl CharacterFilter {
+    pub fn matches(&self, character_id: i32, character: &Character) -> bool {
+        match self {
+            CharacterFilter::Any => true,
+            CharacterFilter::Boss => matches!(character.controller, Controller::Boss { .. }),
+            CharacterFilter::Character(id) => *id == character_id,
+            CharacterFilter::Name(name) => character.name == *name,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum Resource {
+    /// Item(item_id)
+    Item(i32),
+    Gold,
+    Wood,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum Reward {
+    Gold(i32),
+    Wood(i32),
+    Exp(i32),
+    /// Item(item_id, quantity)
+    Item(i32, i32),
+}
+
+/// Something that happened in the game, quests count them
+#[derive(Debug, Clone, Copy)]
+pub enum QuestEvent {
+    /// Defeated(target_id, source_id)
+    Defeated(i32, Option<i32>),
+    /// Reached(character_id, area_id)
+    Reached(i32, i32),
+    /// Talked(character_id, target_id)
+    Talked(i32, i32),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActiveQuest {
+    pub quest_id: i32,
+    /// Who holds the quest, gets the rewards
+    pub character_id: i32,
+    pub stage: usize,
+    /// Progress of the objectives of the stage
+    pub progress: Vec<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuestSystem {
+    /// In the order they were started
+    pub active: Vec<ActiveQuest>,
+    pub completed: Vec<i32>,
+}
+
+impl QuestSystem {
+    pub fn active(&self, quest_id: i32) -> Option<&ActiveQuest> {
+        self.active.iter().find(|q| q.quest_id == quest_id)
+    }
+
+    pub fn is_completed(&self, quest_id: i32) -> bool {
+        self.completed.contains(&quest_id)
+    }
+
+    /// Start the quest for the character, unless it is active or completed
+    pub fn start(game: &mut Game, quest_id: i32, character_id: i32) {
+        let quest_system = &game.quest_system;
+        if quest_system.active(quest_id).is_some() || quest_system.is_completed(quest_id) {
+            return;
+        }
+        if !game.quests.contains_key(&quest_id) {
+            log::error!("No quest {}", quest_id);
+            return;
+        }
+        game.quest_system.active.push(ActiveQuest {
+            quest_id,
+            character_id,
+            stage: 0,
+            progress: Vec::new(),
+        });
+        QuestSystem::enter_stage(game, quest_id, 0);
+    }
+
+    /// Go to the next stage, completing the quest after the last one
+    pub fn advance(game: &mut Game, quest_id: i32) {
+        let stage = match game.quest_system.active(quest_id) {
+            Some(active) => active.stage + 1,
+            None => return,
+        };
+        let stages = game.quests.get(&quest_id).map(|q| q.stages.len());
+        if stages.map(|len| stage < len).unwrap_or(false) {
+            QuestSystem::enter_stage(game, quest_id, stage);
+        } else {
+            QuestSystem::complete(game, quest_id);
+        }
+    }
+
+    /// Complete the quest whatever its stage and give the rewards
+    pub fn complete(game: &mut Game, quest_id: i32) {
+        let index = match game
+            .quest_system
+            .active
+            .iter()
+            .position(|q| q.quest_id == quest_id)
+        {
+            Some(index) => index,
+            None => return,
+        };
+        let active = game.quest_system.active.remove(index);
+        game.quest_system.completed.push(quest_id);
+        let rewards = game
+            .quests
+            .get(&quest_id)
+            .map(|q| q.rewards.clone())
+            .unwrap_or_default();
+        let character_id = active.character_id;
+        for reward in rewards {
+            match reward {
+                Reward::Gold(i) => {
+                    if let Some(c) = game.characters.get_mut(&character_id) {
+                        c.gold += i;
+                    }
+                }
+                Reward::Wood(i) => {
+                    if let Some(c) = game.characters.get_mut(&character_id) {
+                        c.wood += i;
+                    }
+                }
+                Reward::Exp(i) => Character::give_exp(game, character_id, i),
+                Reward::Item(item_id, quantity) => {
+                    Character::gain_item(game, character_id, item_id, quantity)
+                }
+            }
+        }
+    }
+
+    /// Count the event for the objectives of the active quests
+    pub fn notify(game: &mut Game, event: QuestEvent) {
+        for i in 0..game.quest_system.active.len() {
+            let active = &game.quest_system.active[i];
+            let objectives = match game
+                .quests
+                .get(&active.quest_id)
+                .and_then(|q| q.stages.get(active.stage))
+            {
+                Some(stage) => &stage.objectives,
+                None => continue,
+            };
+            let counted: Vec<bool> = objectives
+                .iter()
+                .map(|o| QuestSystem::counts(game, active.character_id, o, event))
+                .collect();
+            let active = &mut game.quest_system.active[i];
+            for (progress, counted) in active.progress.iter_mut().zip(counted) {
+                if counted {
+                    *progress += 1;
+                }
+            }
+        }
+        QuestSystem::update(game);
+    }
+
+    /// Refresh Collect objectives and advance the stages that are done
+    pub fn update(game: &mut Game) {
+        let mut done = Vec::new();
+        for active in &mut game.quest_system.active {
+            let objectives = match game
+                .quests
+                .get(&active.quest_id)
+                .and_then(|q| q.stages.get(active.stage))
+            {
+                Some(stage) => &stage.objectives,
+                None => continue,
+            };
+            let character = game.characters.get(&active.character_id);
+            for (progress, objective) in active.progress.iter_mut().zip(objectives) {
+                if let ObjectiveKind::Collect(resource) = &objective.kind {
+                    *progress = match (resource, character) {
+                        (Resource::Item(item_id), Some(c)) => c.item_count(*item_id),
+                        (Resource::Gold, Some(c)) => c.gold,
+                        (Resource::Wood, Some(c)) => c.wood,
+                        (_, None) => 0,
+                    };
+                }
+            }
+            let finished = objectives
+                .iter()
+                .zip(&active.progress)
+                .all(|(o, progress)| *progress >= o.count);
+            if !objectives.is_empty() && finished {
+                done.push(active.quest_id);
+            }
+        }
+        for quest_id in done {
+            QuestSystem::advance(game, quest_id);
+        }
+    }
+
+    fn enter_stage(game: &mut Game, quest_id: i32, stage: usize) {
+        let len = game
+            .quests
+            .get(&quest_id)
+            .and_then(|q| q.stages.get(stage))
+            .map(|s| s.objectives.len())
+            .unwrap_or_default();
+        if let Some(active) = game
+            .quest_system
+            .active
+            .iter_mut()
+            .find(|q| q.quest_id == quest_id)
+        {
+            active.stage = stage;
+            active.progress = vec![0; len];
+        }
+        QuestSystem::update(game);
+    }
+
+    /// Whether the event counts for the objective of the quest held by
+    /// `holder`
+    fn counts(game: &Game, holder: i32, objective: &Objective, event: QuestEvent) -> bool {
+        let (character_id, target_id) = match (&objective.kind, event) {
+            (ObjectiveKind::Defeat(filter), QuestEvent::Defeated(target_id, source_id)) => {
+                let matches = game
+                    .characters
+                    .get(&target_id)
+                    .map(|c| filter.matches(target_id, c))
+                    .unwrap_or(false);
+                if !matches {
+                    return false;
+                }
+                (source_id.unwrap_or(holder), target_id)
+            }
+            (ObjectiveKind::Reach(area_id), QuestEvent::Reached(character_id, id)) => {
+                if *area_id != id || character_id != holder {
+                    return false;
+                }
+                (character_id, character_id)
+            }
+            (ObjectiveKind::Talk(id), QuestEvent::Talked(character_id, target_id)) => {
+                if *id != target_id || character_id != holder {
+                    return false;
+                }
+                (character_id, target_id)
+            }
+            _ => return false,
+        };
+        objective
+            .condition
+            .as_ref()
+            .map(|c| Condition::check(game, c, character_id, target_id))
+            .unwrap_or(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing;
+    use super::*;
+
+    const QUEST: &str = "(
+        id: 99,
+        name: \"quest\",
+        description: \"quest\",
+        stages: [
+            (
+                description: \"stage\",
+                objectives: [
+                    (description: \"talk\", kind: Talk(0), count: 2),
+                    (description: \"defeat\", kind: Defeat(Character(2)), count: 1),
+                ],
+            ),
+        ],
+        rewards: [Gold(7)],
+    )";
+
+    fn progress(game: &Game) -> Vec<i32> {
+        game.quest_system.active(99).unwrap().progress.clone()
+    }
+
+    #[test]
+    fn notify() {
+        let mut game = testing::game();
+        game.quests.insert(99, ron::de::from_str(QUEST).unwrap());
+        QuestSystem::start(&mut game, 99, 1);
+        let gold = game.characters[&1].gold;
+
+        QuestSystem::notify(&mut game, QuestEvent::Talked(1, 0));
+        // Not the holder, not the character
+        QuestSystem::notify(&mut game, QuestEvent::Talked(3, 0));
+        QuestSystem::notify(&mut game, QuestEvent::Talked(1, 5));
+        QuestSystem::notify(&mut game, QuestEvent::Defeated(3, Some(1)));
+        assert_eq!(progress(&game), vec![1, 0]);
+
+        QuestSystem::notify(&mut game, QuestEvent::Talked(1, 0));
+        // Defeated by anyone counts
+        QuestSystem::notify(&mut game, QuestEvent::Defeated(2, None));
+        assert!(game.quest_system.is_completed(99));
+        assert!(game.quest_system.active(99).is_none());
+        assert_eq!(game.characters[&1].gold, gold + 7);
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::{CharacterItem, Game};
+use super::{Character, Game};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ShopSystem {
@@ -22,12 +22,8 @@ impl ShopSystem {
                 .and_then(|items| items.get(index))
                 .map(|si| si.item_id);
             if let Some(id) = id {
-                if let Some(c) = game.characters.get_mut(&current.character_id) {
-                    c.items.push(CharacterItem {
-                        item_id: id,
-                        quantity: 1,
-                    });
-                }
+                let character_id = current.character_id;
+                Character::gain_item(game, character_id, id, 1);
             }
         }
     }
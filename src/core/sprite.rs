@@ -6,6 +6,8 @@ use super::Position;
 pub struct CharacterSprite {
     pub id: i32,
     pub avatar: String,
+    /// Image shown next to the character's lines in novel scenes
+    pub portrait: Option<String>,
     pub animation: HashMap<String, Animation>,
 }
 
@@ -0,0 +1,58 @@
+use super::{Game, Localization, TileMap};
+use serde::de::DeserializeOwned;
+use std::{fs, path::Path};
+
+fn load<T: DeserializeOwned>(name: &str) -> T {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("resources/game")
+        .join(format!("{}.ron", name));
+    let source = fs::read_to_string(&path).unwrap();
+    ron::de::from_str(&source).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+/// The shipped game data, as the game loads it
+pub fn game() -> Game {
+    let mut localization: Localization = load("localization");
+    for language in &["en", "fr"] {
+        let table = load(&format!("lang/{}", language));
+        localization.tables.insert(String::from(*language), table);
+    }
+    Game {
+        characters: load("characters"),
+        commands: load("commands"),
+        character_actions: load("character_actions"),
+        character_templates: load("character_templates"),
+        actions: load("actions"),
+        items: load("items"),
+        tile_maps: load("tile_maps"),
+        teleportations: load("teleportations"),
+        tiles: load("tiles"),
+        tile_sheets: load("tile_sheets"),
+        character_sprites: load("character_sprites"),
+        sprite_sheets: load("sprite_sheets"),
+        sprite_sequences: load("sprite_sequences"),
+        sprite_animations: load("sprite_animations"),
+        areas: load("areas"),
+        player_classes: load("player_classes"),
+        quests: load("quests"),
+        camera: load("camera"),
+        turn_system: load("turn_system"),
+        novel_system: load("novel_system"),
+        shop_system: load("shop_system"),
+        floor_system: load("floor_system"),
+        variable_system: load("variable_system"),
+        quest_system: load("quest_system"),
+        localization,
+        events: Default::default(),
+        exploration_system: Default::default(),
+    }
+}
+
+/// Add a map drawn with `.` for floor and `#` for wall
+pub fn add_map(game: &mut Game, id: i32, rows: &[&str]) {
+    let tiles = rows
+        .iter()
+        .map(|row| row.chars().map(|c| if c == '#' { 1 } else { 0 }).collect())
+        .collect();
+    game.tile_maps.insert(id, TileMap { id, tiles });
+}
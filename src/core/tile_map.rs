@@ -1,5 +1,10 @@
 use serde::{Deserialize, Serialize};
 
+use super::CrowdControlKind;
+
+/// Highest elevation difference a character can step over in one move.
+pub const MAX_CLIMB: i32 = 1;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TileMap {
     pub id: i32,
@@ -9,9 +14,32 @@ pub struct TileMap {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Tile {
     pub walkable: bool,
+    /// Turn time spent when moving onto this tile
+    #[serde(default = "default_cost")]
+    pub cost: i32,
+    #[serde(default)]
+    pub block_sight: bool,
+    #[serde(default)]
+    pub block_projectile: bool,
+    #[serde(default)]
+    pub elevation: i32,
+    /// Applied to a character moving onto this tile
+    #[serde(default)]
+    pub hazard: Option<Hazard>,
     pub kind: TileKind,
 }
 
+fn default_cost() -> i32 {
+    1
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Hazard {
+    pub damage: i32,
+    /// (kind, duration)
+    pub crowd_control: Option<(CrowdControlKind, i32)>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum TileKind {
     /// TileSheet(id, x, y)
@@ -26,3 +54,18 @@ pub struct TileSheet {
     pub tile_width: i32,
     pub tile_height: i32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_defaults() {
+        let tile: Tile = ron::de::from_str("(walkable: true, kind: Image(\"/a.png\"))").unwrap();
+        assert_eq!(tile.cost, 1);
+        assert!(!tile.block_sight);
+        assert!(!tile.block_projectile);
+        assert_eq!(tile.elevation, 0);
+        assert!(tile.hazard.is_none());
+    }
+}
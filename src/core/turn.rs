@@ -16,7 +16,79 @@ pub struct Turn {
     pub kind: TurnKind,
 }
 
+/// Turn of a character in the timeline, `time` from now
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimelineTurn {
+    pub character_id: i32,
+    pub time: i32,
+}
+
 impl TurnSystem {
+    /// The next `count` turns of characters on the map, the current turn
+    /// first. The current turn costs `current_time`, for example the turn time
+    /// of the action being picked, later turns are assumed to cost 1.
+    pub fn timeline(
+        game: &Game,
+        map_id: i32,
+        current_time: Option<i32>,
+        count: usize,
+    ) -> Vec<TimelineTurn> {
+        // (time, character_id, respawn)
+        let mut queue: Vec<(i32, i32, bool)> = game
+            .turn_system
+            .turn_queue
+            .iter()
+            .map(|t| match t.kind {
+                TurnKind::Character { character_id } => (t.time, character_id, false),
+                TurnKind::Respawn { character_id } => (t.time, character_id, true),
+            })
+            .collect();
+        // Same order as Game::add_turn
+        let add = |queue: &mut Vec<(i32, i32, bool)>, turn: (i32, i32, bool)| {
+            let index = queue
+                .iter()
+                .position(|t| t.0 > turn.0)
+                .unwrap_or(queue.len());
+            queue.insert(index, turn);
+        };
+        let shown = |character_id: i32| {
+            game.characters
+                .get(&character_id)
+                .map(|c| c.position.map_id == map_id && !c.dead && !c.defeated)
+                .unwrap_or(false)
+        };
+
+        let mut timeline = Vec::new();
+        if let Some(Turn {
+            kind: TurnKind::Character { character_id },
+            ..
+        }) = game.turn_system.current_turn
+        {
+            if shown(character_id) {
+                timeline.push(TimelineTurn {
+                    character_id,
+                    time: 0,
+                });
+            }
+            add(
+                &mut queue,
+                (current_time.unwrap_or(1).max(1), character_id, false),
+            );
+        }
+
+        // Turns of other maps are skipped, stop if the map has none
+        let mut steps = (count + 1) * (queue.len() + 1);
+        while timeline.len() < count && steps > 0 && !queue.is_empty() {
+            steps -= 1;
+            let (time, character_id, respawn) = queue.remove(0);
+            if !respawn && shown(character_id) {
+                timeline.push(TimelineTurn { character_id, time });
+            }
+            add(&mut queue, (time + 1, character_id, false));
+        }
+        timeline
+    }
+
     pub fn update(game: &mut Game, delta: f32) {
         loop {
             if game.turn_system.pause {
@@ -98,6 +170,7 @@ impl TurnSystem {
                                 })
                                 .filter(|(_, c)| c.race == Race::Human)
                                 .filter(|(_, c)| !c.dead)
+                                .filter(|(_, c)| game.line_of_sight(position, &c.position))
                                 .collect();
                             humans.sort_by_key(|(_, c)| Position::distance(&c.position, &position));
                             if humans.is_empty() {
@@ -125,24 +198,22 @@ impl TurnSystem {
                                     }
                                 } else {
                                     // move
-                                    let x = human.position.x - c.position.x;
-                                    let y = human.position.y - c.position.y;
-                                    if x.abs() > y.abs() {
-                                        let command = Character::move_command(
-                                            game,
-                                            character_id,
-                                            if x.is_positive() { 1 } else { -1 },
-                                            0,
-                                        );
-                                        game.set_character_command(character_id, command.unwrap());
-                                    } else {
-                                        let command = Character::move_command(
-                                            game,
-                                            character_id,
-                                            0,
-                                            if y.is_positive() { 1 } else { -1 },
-                                        );
-                                        game.set_character_command(character_id, command.unwrap());
+                                    match game.path_step(&c.position, &human.position) {
+                                        Some((x, y)) => {
+                                            let command =
+                                                Character::move_command(game, character_id, x, y);
+                                            game.set_character_command(
+                                                character_id,
+                                                command.unwrap(),
+                                            );
+                                        }
+                                        None => {
+                                            game.add_command(
+                                                character_id,
+                                                String::from("Idle"),
+                                                Target::None,
+                                            );
+                                        }
                                     }
                                 }
                             }
@@ -168,30 +239,26 @@ impl TurnSystem {
                                         );
                                     } else {
                                         // move
-                                        let x = target.position.x - c.position.x;
-                                        let y = target.position.y - c.position.y;
-                                        if x.abs() > y.abs() {
-                                            let command = Character::move_command(
-                                                game,
-                                                character_id,
-                                                if x.is_positive() { 1 } else { -1 },
-                                                0,
-                                            );
-                                            game.set_character_command(
-                                                character_id,
-                                                command.unwrap(),
-                                            );
-                                        } else {
-                                            let command = Character::move_command(
-                                                game,
-                                                character_id,
-                                                0,
-                                                if y.is_positive() { 1 } else { -1 },
-                                            );
-                                            game.set_character_command(
-                                                character_id,
-                                                command.unwrap(),
-                                            );
+                                        match game.path_step(&c.position, &target.position) {
+                                            Some((x, y)) => {
+                                                let command = Character::move_command(
+                                                    game,
+                                                    character_id,
+                                                    x,
+                                                    y,
+                                                );
+                                                game.set_character_command(
+                                                    character_id,
+                                                    command.unwrap(),
+                                                );
+                                            }
+                                            None => {
+                                                game.add_command(
+                                                    character_id,
+                                                    String::from("Idle"),
+                                                    Target::None,
+                                                );
+                                            }
                                         }
                                     }
                                 } else {
@@ -301,7 +368,11 @@ impl TurnSystem {
         let character = game.characters.get_mut(&character_id).unwrap();
         if let Some(command_id) = character.command_id {
             Command::end(game, command_id);
+            let time = Command::turn_time(game, command_id);
             game.commands.remove(&command_id);
+            if let Some(t) = &mut game.turn_system.current_turn {
+                t.time = time;
+            }
         }
         let character = game.characters.get_mut(&character_id).unwrap();
         character.command_id = None;
@@ -311,7 +382,7 @@ impl TurnSystem {
         if let Some(mut t) = game.turn_system.current_turn.take() {
             match t.kind {
                 TurnKind::Character { character_id } => {
-                    t.time = 1;
+                    t.time = t.time.max(1);
                     game.add_turn(t);
 
                     let c = game.characters.get_mut(&character_id).unwrap();
@@ -341,3 +412,51 @@ pub enum State {
     EndCommand,
     EndTurn,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing;
+    use super::*;
+
+    fn turn(time: i32, character_id: i32) -> Turn {
+        Turn {
+            time,
+            kind: TurnKind::Character { character_id },
+        }
+    }
+
+    fn timeline(game: &Game, current_time: Option<i32>, count: usize) -> Vec<(i32, i32)> {
+        TurnSystem::timeline(game, 1, current_time, count)
+            .iter()
+            .map(|t| (t.character_id, t.time))
+            .collect()
+    }
+
+    #[test]
+    fn timeline_order() {
+        let mut game = testing::game();
+        game.turn_system.turn_queue = vec![turn(0, 2), turn(1, 3)];
+        game.turn_system.current_turn = Some(turn(0, 4));
+        assert_eq!(
+            timeline(&game, Some(3), 6),
+            vec![(4, 0), (2, 0), (3, 1), (2, 1), (3, 2), (2, 2)]
+        );
+        // A cheaper action comes back sooner
+        assert_eq!(
+            timeline(&game, Some(1), 4),
+            vec![(4, 0), (2, 0), (3, 1), (4, 1)]
+        );
+    }
+
+    #[test]
+    fn timeline_skips_other_maps() {
+        let mut game = testing::game();
+        game.turn_system.turn_queue = vec![turn(0, 1), turn(0, 2), turn(0, 3)];
+        game.turn_system.current_turn = None;
+        game.characters.get_mut(&3).unwrap().dead = true;
+        // The player is on another map, the dead are skipped too
+        assert_eq!(timeline(&game, None, 3), vec![(2, 0), (2, 1), (2, 2)]);
+        game.characters.get_mut(&2).unwrap().position.map_id = 0;
+        assert!(timeline(&game, None, 3).is_empty());
+    }
+}
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VariableSystem {
+    pub values: HashMap<String, Value>,
+}
+
+impl VariableSystem {
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values.get(name)
+    }
+
+    pub fn set(&mut self, name: &str, value: Value) {
+        self.values.insert(String::from(name), value);
+    }
+
+    /// Add to an int value, a missing or non-int value counts as 0
+    pub fn add(&mut self, name: &str, i: i32) {
+        let value = match self.values.get(name) {
+            Some(Value::Int(v)) => v + i,
+            _ => i,
+        };
+        self.set(name, Value::Int(value));
+    }
+
+    pub fn clear(&mut self, name: &str) {
+        self.values.remove(name);
+    }
+
+    /// Replace `{name}` with the value of the variable, unknown names are kept as is
+    pub fn interpolate(&self, text: &str) -> String {
+        VariableSystem::interpolate_with(text, |name| {
+            self.values.get(name).map(|value| value.to_string())
+        })
+    }
+
+    /// Replace `{name}` with `lookup(name)`, names without a value are kept as is
+    pub fn interpolate_with<F>(text: &str, lookup: F) -> String
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut s = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find('{') {
+            s.push_str(&rest[..start]);
+            match rest[start..].find('}') {
+                Some(end) => {
+                    let name = &rest[start + 1..start + end];
+                    match lookup(name) {
+                        Some(value) => s.push_str(&value),
+                        None => s.push_str(&rest[start..=start + end]),
+                    }
+                    rest = &rest[start + end + 1..];
+                }
+                None => {
+                    s.push_str(&rest[start..]);
+                    rest = "";
+                }
+            }
+        }
+        s.push_str(rest);
+        s
+    }
+
+    /// Unset values and `Bool(false)` count as not set
+    pub fn is_set(&self, name: &str) -> bool {
+        match self.values.get(name) {
+            None => false,
+            Some(Value::Bool(b)) => *b,
+            Some(_) => true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum Value {
+    Bool(bool),
+    Int(i32),
+    Str(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Str(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables() -> VariableSystem {
+        let mut v = VariableSystem {
+            values: HashMap::new(),
+        };
+        v.set("visits", Value::Int(3));
+        v.set("met", Value::Bool(false));
+        v
+    }
+
+    #[test]
+    fn interpolate() {
+        let v = variables();
+        assert_eq!(v.interpolate("Came {visits} times"), "Came 3 times");
+        assert_eq!(v.interpolate("{visits}{visits}"), "33");
+        assert_eq!(v.interpolate("Hello {who}"), "Hello {who}");
+        assert_eq!(v.interpolate("Open {visits"), "Open {visits");
+        assert_eq!(v.interpolate("No braces"), "No braces");
+    }
+
+    #[test]
+    fn add_and_is_set() {
+        let mut v = variables();
+        v.add("visits", 2);
+        v.add("new", 1);
+        assert_eq!(v.get("visits"), Some(&Value::Int(5)));
+        assert_eq!(v.get("new"), Some(&Value::Int(1)));
+        assert!(v.is_set("visits"));
+        assert!(!v.is_set("met"));
+        v.clear("visits");
+        assert!(!v.is_set("visits"));
+    }
+}
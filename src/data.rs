@@ -1,14 +1,11 @@
 use std::collections::HashMap;
 
 use ggez::{graphics, Context, GameResult};
-use serde::{Deserialize, Serialize};
-use tower::core::{Game, Target};
+use tower::core::Game;
 
 #[derive(Debug)]
 pub struct Data {
     pub game: Game,
-    pub target_scene: TargetSceneData,
-    pub action_scene: ActionSceneData,
     pub image_caches: ImageCache,
 }
 
@@ -16,8 +13,6 @@ impl Data {
     pub fn new(game: Game) -> Self {
         Self {
             game,
-            target_scene: Default::default(),
-            action_scene: Default::default(),
             image_caches: Default::default(),
         }
     }
@@ -37,12 +32,3 @@ impl ImageCache {
         Ok(self.resources.get(key).unwrap())
     }
 }
-
-#[derive(Debug, Serialize, Deserialize, Default)]
-pub struct TargetSceneData {
-    pub finish: bool,
-}
-#[derive(Debug, Serialize, Deserialize, Default)]
-pub struct ActionSceneData {
-    pub action_id: Option<i32>,
-}
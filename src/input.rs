@@ -0,0 +1,169 @@
+use ggez::event::{Button, KeyCode, MouseButton};
+use ggez::mint::Point2;
+use serde::{Deserialize, Serialize};
+
+/// What the player wants, scenes receive these instead of raw events
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Cancel,
+    /// Open or close the pause menu
+    Menu,
+    Backlog,
+    Skip,
+    Auto,
+    Journal,
+    Inventory,
+    CharacterSheet,
+    /// Throw away the selected item
+    Drop,
+    /// Scroll the combat log toward older events
+    ScrollUp,
+    ScrollDown,
+    /// Open or close the full map
+    Map,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Input {
+    pub action: Action,
+    /// Screen position of the mouse, for actions bound to mouse buttons
+    pub pointer: Option<Point2<f32>>,
+}
+
+impl Input {
+    pub fn new(action: Action) -> Self {
+        Self {
+            action,
+            pointer: None,
+        }
+    }
+}
+
+/// Raw event from a device
+#[derive(Debug, Clone, Copy)]
+pub enum RawInput {
+    Key(KeyCode),
+    /// Mouse(button, x, y)
+    Mouse(MouseButton, f32, f32),
+    Button(Button),
+    /// Wheel(y), positive when scrolled away from the user
+    Wheel(f32),
+}
+
+/// A key, mouse button or gamepad button, named as ggez names them, for
+/// example `Key("Escape")`, `Mouse("Left")` or `Button("South")`, the mouse
+/// wheel is `Wheel("Up")` or `Wheel("Down")`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum Binding {
+    Key(String),
+    Mouse(String),
+    Button(String),
+    Wheel(String),
+}
+
+/// Bindings from `/input.ron`, a device input can trigger several actions
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Bindings {
+    pub bindings: Vec<(Binding, Action)>,
+}
+
+impl Bindings {
+    /// Actions bound to the raw event, in the order of the bindings
+    pub fn map(&self, raw: RawInput) -> Vec<Input> {
+        let (binding, pointer) = match raw {
+            RawInput::Key(keycode) => (Binding::Key(format!("{:?}", keycode)), None),
+            RawInput::Mouse(button, x, y) => (
+                Binding::Mouse(format!("{:?}", button)),
+                Some(Point2 { x, y }),
+            ),
+            RawInput::Button(button) => (Binding::Button(format!("{:?}", button)), None),
+            RawInput::Wheel(y) if y > 0.0 => (Binding::Wheel(String::from("Up")), None),
+            RawInput::Wheel(_) => (Binding::Wheel(String::from("Down")), None),
+        };
+        self.bindings
+            .iter()
+            .filter(|(b, _)| *b == binding)
+            .map(|(_, action)| Input {
+                action: *action,
+                pointer,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bindings() -> Bindings {
+        Bindings {
+            bindings: vec![
+                (Binding::Key(String::from("W")), Action::Up),
+                (Binding::Mouse(String::from("Left")), Action::Confirm),
+                (Binding::Wheel(String::from("Up")), Action::ScrollUp),
+                (Binding::Wheel(String::from("Down")), Action::ScrollDown),
+                (Binding::Key(String::from("Space")), Action::Confirm),
+                (Binding::Key(String::from("Space")), Action::Skip),
+            ],
+        }
+    }
+
+    fn actions(inputs: &[Input]) -> Vec<Action> {
+        inputs.iter().map(|i| i.action).collect()
+    }
+
+    #[test]
+    fn key() {
+        let inputs = bindings().map(RawInput::Key(KeyCode::W));
+        assert_eq!(actions(&inputs), vec![Action::Up]);
+        assert!(inputs[0].pointer.is_none());
+        assert!(bindings().map(RawInput::Key(KeyCode::S)).is_empty());
+    }
+
+    #[test]
+    fn mouse_button_with_pointer() {
+        let inputs = bindings().map(RawInput::Mouse(MouseButton::Left, 12.0, 34.0));
+        assert_eq!(actions(&inputs), vec![Action::Confirm]);
+        let pointer = inputs[0].pointer.unwrap();
+        assert_eq!((pointer.x, pointer.y), (12.0, 34.0));
+        assert!(bindings()
+            .map(RawInput::Mouse(MouseButton::Right, 0.0, 0.0))
+            .is_empty());
+    }
+
+    #[test]
+    fn wheel() {
+        let up = bindings().map(RawInput::Wheel(1.0));
+        assert_eq!(actions(&up), vec![Action::ScrollUp]);
+        let down = bindings().map(RawInput::Wheel(-1.0));
+        assert_eq!(actions(&down), vec![Action::ScrollDown]);
+    }
+
+    #[test]
+    fn key_bound_to_several_actions() {
+        let inputs = bindings().map(RawInput::Key(KeyCode::Space));
+        assert_eq!(actions(&inputs), vec![Action::Confirm, Action::Skip]);
+    }
+
+    #[test]
+    fn shipped_bindings_are_distinct() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/resources/input.ron");
+        let source = std::fs::read_to_string(path).unwrap();
+        let shipped: Bindings = ron::de::from_str(&source).unwrap();
+        for (i, (binding, action)) in shipped.bindings.iter().enumerate() {
+            let other = shipped.bindings[i + 1..].iter().find(|(b, _)| b == binding);
+            assert!(
+                other.is_none(),
+                "{:?} bound to {:?} and {:?}",
+                binding,
+                action,
+                other
+            );
+        }
+    }
+}
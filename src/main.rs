@@ -1,17 +1,19 @@
 use data::Data;
-use ggez::event::{self, EventHandler};
-use ggez::{conf, event::KeyCode};
-use ggez::{event::KeyMods, filesystem};
+use ggez::event::{self, Button, EventHandler, GamepadId, KeyCode, KeyMods, MouseButton};
+use ggez::{conf, filesystem};
 use ggez::{Context, ContextBuilder, GameResult};
+use input::{Bindings, RawInput};
 use scene::SceneStack;
-use scenes::{action_scene::ActionScene, level_scene::LevelScene};
+use scenes::{action_scene::ActionScene, title_scene::TitleScene};
 use std::{env, path};
-use tower::core::Game;
+use tower::core::analyze_scripts;
 
 mod data;
+mod input;
 mod rendering;
 mod scene;
 mod scenes;
+mod storage;
 mod ui;
 
 fn setup_logger() -> Result<(), fern::InitError> {
@@ -86,6 +88,7 @@ pub struct MainState {
     // Your state here...\
     pub scene_stack: SceneStack,
     pub data: data::Data,
+    pub bindings: Bindings,
 }
 
 impl MainState {
@@ -97,92 +100,31 @@ impl MainState {
         // let pretty = ron::ser::PrettyConfig::new();
         // ron::ser::to_writer_pretty(f, &data, pretty).unwrap();
 
-        let characters = filesystem::open(ctx, "/game/characters.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let commands = filesystem::open(ctx, "/game/commands.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let character_actions = filesystem::open(ctx, "/game/character_actions.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let actions = filesystem::open(ctx, "/game/actions.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let items = filesystem::open(ctx, "/game/items.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let tile_maps = filesystem::open(ctx, "/game/tile_maps.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let teleportations = filesystem::open(ctx, "/game/teleportations.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let tiles = filesystem::open(ctx, "/game/tiles.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let tile_sheets = filesystem::open(ctx, "/game/tile_sheets.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let character_sprites = filesystem::open(ctx, "/game/character_sprites.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let sprite_sheets = filesystem::open(ctx, "/game/sprite_sheets.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let sprite_sequences = filesystem::open(ctx, "/game/sprite_sequences.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let sprite_animations = filesystem::open(ctx, "/game/sprite_animations.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let areas = filesystem::open(ctx, "/game/areas.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let camera = filesystem::open(ctx, "/game/camera.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let turn_system = filesystem::open(ctx, "/game/turn_system.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let novel_system = filesystem::open(ctx, "/game/novel_system.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let shop_system = filesystem::open(ctx, "/game/shop_system.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let floor_system = filesystem::open(ctx, "/game/floor_system.ron")
-            .map(|f| ron::de::from_reader(f).unwrap())
-            .unwrap();
-        let game = Game {
-            characters,
-            commands,
-            character_actions,
-            actions,
-            items,
-            tile_maps,
-            teleportations,
-            tiles,
-            tile_sheets,
-            character_sprites,
-            sprite_sheets,
-            sprite_sequences,
-            sprite_animations,
-            areas,
-            camera,
-            turn_system,
-            novel_system,
-            shop_system,
-            floor_system,
-        };
-        // let f = filesystem::open(ctx, "/game.ron").unwrap();
-        // let game: Game = ron::de::from_reader(f).unwrap();
+        let mut game = storage::load_game(ctx);
+        for issue in analyze_scripts(
+            &game.novel_system,
+            &game.characters,
+            &game.character_templates,
+            &game.tile_maps,
+            &game.tiles,
+        ) {
+            log::warn!("{}", issue);
+        }
+        if let Some(settings) = storage::load_settings(ctx) {
+            settings.apply(&mut game);
+        }
         let mut data = Data::new(game);
         let mut scene_stack = SceneStack::new();
-        scene_stack
-            .stack
-            .push(Box::new(LevelScene::new(ctx, &mut data)));
-        MainState { scene_stack, data }
+        let title_scene = Box::new(TitleScene::new(ctx, &mut data));
+        scene_stack.push(ctx, &mut data, title_scene);
+        let bindings = filesystem::open(ctx, "/input.ron")
+            .map(|f| ron::de::from_reader(f).unwrap())
+            .unwrap();
+        MainState {
+            scene_stack,
+            data,
+            bindings,
+        }
     }
 }
 
@@ -203,10 +145,31 @@ impl EventHandler for MainState {
         &mut self,
         ctx: &mut Context,
         keycode: KeyCode,
-        keymods: KeyMods,
-        repeat: bool,
+        _keymods: KeyMods,
+        _repeat: bool,
     ) {
-        self.scene_stack
-            .key_down_event(ctx, keycode, keymods, repeat, &mut self.data);
+        let inputs = self.bindings.map(RawInput::Key(keycode));
+        self.scene_stack.input(ctx, &mut self.data, inputs);
+    }
+
+    fn text_input_event(&mut self, ctx: &mut Context, ch: char) {
+        self.scene_stack.text_input(ctx, &mut self.data, ch);
+    }
+
+    fn mouse_button_down_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        let inputs = self.bindings.map(RawInput::Mouse(button, x, y));
+        self.scene_stack.input(ctx, &mut self.data, inputs);
+    }
+
+    fn mouse_wheel_event(&mut self, ctx: &mut Context, _x: f32, y: f32) {
+        if y != 0.0 {
+            let inputs = self.bindings.map(RawInput::Wheel(y));
+            self.scene_stack.input(ctx, &mut self.data, inputs);
+        }
+    }
+
+    fn gamepad_button_down_event(&mut self, ctx: &mut Context, button: Button, _id: GamepadId) {
+        let inputs = self.bindings.map(RawInput::Button(button));
+        self.scene_stack.input(ctx, &mut self.data, inputs);
     }
 }
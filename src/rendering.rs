@@ -2,7 +2,7 @@ use ggez::{
     graphics::{self, Drawable, Image},
     Context, GameResult,
 };
-use tower::core::{Character, Game, Position};
+use tower::core::{Character, Controller, Game, Position};
 
 pub struct Rendering;
 
@@ -11,6 +11,7 @@ pub struct RenderingParam {
     dest: Option<Destination>,
     rect: Option<RectSetting>,
     target: Option<TargetSize>,
+    color: Option<graphics::Color>,
 }
 struct Destination {
     pub map_id: i32,
@@ -61,15 +62,21 @@ impl RenderingParam {
         self
     }
 
+    /// Tint of the image, the alpha fades it
+    pub fn color(mut self, color: graphics::Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
     pub fn draw_image(&self, game: &Game, image: &Image, ctx: &mut Context) -> GameResult {
-        if let Some(character) = game.characters.get(&game.camera.character_id) {
+        if let Some((map_id, x, y)) = game.camera_center() {
             let mut param = graphics::DrawParam::default();
             if let Some(dest) = &self.dest {
-                if dest.map_id != character.position.map_id {
+                if dest.map_id != map_id {
                     return Ok(());
                 }
-                if (character.position.x as f32 - dest.x).abs() > game.camera.extend as f32
-                    || (character.position.y as f32 - dest.y).abs() > game.camera.extend as f32
+                if (x - dest.x).abs() > game.camera.extend as f32
+                    || (y - dest.y).abs() > game.camera.extend as f32
                 {
                     return Ok(());
                 }
@@ -104,6 +111,10 @@ impl RenderingParam {
                 param = param.scale(scale);
             }
 
+            if let Some(color) = self.color {
+                param = param.color(color);
+            }
+
             graphics::draw(ctx, image, param)?;
         }
         Ok(())
@@ -111,14 +122,22 @@ impl RenderingParam {
 }
 
 impl Rendering {
+    /// Players green, enemies red, bosses purple, others grey
+    pub fn controller_color(controller: &Controller) -> graphics::Color {
+        match controller {
+            Controller::Player => graphics::Color::from_rgb(0, 255, 0),
+            Controller::Enemy => graphics::Color::from_rgb(255, 0, 0),
+            Controller::Boss { .. } => graphics::Color::from_rgb(191, 0, 255),
+            Controller::NPC => graphics::Color::from_rgb(127, 127, 127),
+        }
+    }
+
     fn dest(game: &Game, x: f32, y: f32) -> Option<ggez::mint::Point2<f32>> {
-        if let Some(character) = game.characters.get(&game.camera.character_id) {
+        if let Some((_, center_x, center_y)) = game.camera_center() {
             Some(ggez::mint::Point2 {
-                x: (((-character.position.x + game.camera.extend) as f32 + x)
-                    * game.camera.tile_size as f32
+                x: ((-center_x + game.camera.extend as f32 + x) * game.camera.tile_size as f32
                     + game.camera.border as f32) as f32,
-                y: (((-character.position.y + game.camera.extend) as f32 + y)
-                    * game.camera.tile_size as f32
+                y: ((-center_y + game.camera.extend as f32 + y) * game.camera.tile_size as f32
                     + game.camera.border as f32) as f32,
             })
         } else {
@@ -126,7 +145,7 @@ impl Rendering {
         }
     }
 
-    fn draw<D>(
+    pub fn draw<D>(
         ctx: &mut Context,
         drawable: &D,
         map_id: i32,
@@ -137,12 +156,12 @@ impl Rendering {
     where
         D: Drawable,
     {
-        if let Some(character) = game.characters.get(&game.camera.character_id) {
-            if map_id != character.position.map_id {
+        if let Some((center_map_id, center_x, center_y)) = game.camera_center() {
+            if map_id != center_map_id {
                 return Ok(());
             }
-            if (character.position.x as f32 - x).abs() > game.camera.extend as f32
-                || (character.position.y as f32 - y).abs() > game.camera.extend as f32
+            if (center_x - x).abs() > game.camera.extend as f32
+                || (center_y - y).abs() > game.camera.extend as f32
             {
                 return Ok(());
             }
@@ -203,6 +222,21 @@ impl Rendering {
         Ok(())
     }
 
+    /// Color of the camera fade over the map
+    pub fn draw_fade(ctx: &mut Context, game: &Game) -> GameResult<()> {
+        if let Some(fade) = &game.camera.fade {
+            let (r, g, b) = fade.color;
+            let mesh = graphics::Mesh::new_rectangle(
+                ctx,
+                graphics::DrawMode::fill(),
+                graphics::Rect::new_i32(0, 0, 1280, 720),
+                graphics::Color::from_rgba(r, g, b, fade.alpha()),
+            )?;
+            graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+        }
+        Ok(())
+    }
+
     pub fn draw_border(ctx: &mut Context, game: &Game) -> GameResult<()> {
         // Draw Border
         let size = (game.camera.extend * 2 + 1) * game.camera.tile_size;
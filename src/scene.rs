@@ -1,30 +1,62 @@
+use ggez::graphics;
 use ggez::Context;
-use ggez::{event::KeyCode, GameResult};
-use ggez::{event::KeyMods, graphics};
+use ggez::GameResult;
+use std::any::Any;
 
 pub use crate::data::Data;
+use crate::input::Input;
 
-pub trait Scene {
+pub trait Scene: Any {
     fn update(&mut self, _ctx: &mut Context, data: &mut Data) -> GameResult<Transition>;
     fn draw(&mut self, ctx: &mut Context, data: &mut Data) -> GameResult;
 
-    fn key_down_event(
+    /// Drawn over all the scenes, only for the scene on top
+    fn draw_top(&mut self, _ctx: &mut Context, _data: &mut Data) -> GameResult {
+        Ok(())
+    }
+
+    fn input(&mut self, ctx: &mut Context, input: Input, data: &mut Data) -> Transition;
+
+    /// Typed character, for text fields. Comes after the actions of the key.
+    fn text_input(&mut self, _ctx: &mut Context, _ch: char, _data: &mut Data) {}
+
+    /// Pushed on the stack
+    fn on_enter(&mut self, _ctx: &mut Context, _data: &mut Data) {}
+
+    /// Back on top after the scenes above were popped
+    fn on_resume(&mut self, _ctx: &mut Context, _data: &mut Data) {}
+
+    /// Removed from the stack
+    fn on_exit(&mut self, _ctx: &mut Context, _data: &mut Data) {}
+
+    /// Value of a `PopWith` of the scene above, before `on_resume`
+    fn on_result(
         &mut self,
-        ctx: &mut Context,
-        keycode: KeyCode,
-        _keymods: KeyMods,
-        _repeat: bool,
-        data: &mut Data,
-    ) -> Transition;
+        _ctx: &mut Context,
+        _data: &mut Data,
+        _result: Box<dyn Any>,
+    ) -> Transition {
+        Transition::None
+    }
 }
 
 pub enum Transition {
     None,
     Pop,
+    /// Pop and hand the value to the scene below
+    PopWith(Box<dyn Any>),
+    /// Pop this many scenes
+    PopN(usize),
     Push(Box<dyn Scene>),
     Replace(Box<dyn Scene>),
 }
 
+impl Transition {
+    pub fn pop_with<T: Any>(value: T) -> Self {
+        Transition::PopWith(Box::new(value))
+    }
+}
+
 pub struct SceneStack {
     pub stack: Vec<Box<dyn Scene>>,
 }
@@ -36,18 +68,53 @@ impl SceneStack {
         }
     }
 
-    fn do_transition(&mut self, tran: Transition) {
+    pub fn push(&mut self, ctx: &mut Context, data: &mut Data, mut scene: Box<dyn Scene>) {
+        scene.on_enter(ctx, data);
+        self.stack.push(scene);
+    }
+
+    /// Remove the top `n` scenes, top first, none if there are not that many
+    fn take(&mut self, n: usize) -> Vec<Box<dyn Scene>> {
+        if n > self.stack.len() {
+            log::error!("Cannot pop {} of {} scenes", n, self.stack.len());
+            return Vec::new();
+        }
+        let mut scenes = self.stack.split_off(self.stack.len() - n);
+        scenes.reverse();
+        scenes
+    }
+
+    /// Pop `n` scenes, give the result to the new top scene then resume it
+    fn pop(&mut self, ctx: &mut Context, data: &mut Data, n: usize, result: Option<Box<dyn Any>>) {
+        let scenes = self.take(n);
+        if scenes.is_empty() {
+            return;
+        }
+        for mut scene in scenes {
+            scene.on_exit(ctx, data);
+        }
+        let mut tran = Transition::None;
+        if let Some(scene) = self.stack.last_mut() {
+            if let Some(result) = result {
+                tran = scene.on_result(ctx, data, result);
+            }
+            scene.on_resume(ctx, data);
+        }
+        self.do_transition(ctx, data, tran);
+    }
+
+    fn do_transition(&mut self, ctx: &mut Context, data: &mut Data, tran: Transition) {
         match tran {
             Transition::None => {}
-            Transition::Pop => {
-                self.stack.pop();
-            }
-            Transition::Push(s) => {
-                self.stack.push(s);
-            }
+            Transition::Pop => self.pop(ctx, data, 1, None),
+            Transition::PopWith(result) => self.pop(ctx, data, 1, Some(result)),
+            Transition::PopN(n) => self.pop(ctx, data, n, None),
+            Transition::Push(s) => self.push(ctx, data, s),
             Transition::Replace(s) => {
-                self.stack.clear();
-                self.stack.push(s);
+                while let Some(mut scene) = self.stack.pop() {
+                    scene.on_exit(ctx, data);
+                }
+                self.push(ctx, data, s);
             }
         }
     }
@@ -64,7 +131,7 @@ impl SceneStack {
 
         if let Some(scene) = self.stack.last_mut() {
             let tran = scene.update(ctx, data)?;
-            self.do_transition(tran);
+            self.do_transition(ctx, data, tran);
         }
 
         Ok(())
@@ -76,23 +143,77 @@ impl SceneStack {
         for scene in &mut self.stack {
             scene.draw(ctx, data)?;
         }
+        if let Some(scene) = self.stack.last_mut() {
+            scene.draw_top(ctx, data)?;
+        }
 
         graphics::present(ctx)?;
 
         Ok(())
     }
 
-    pub fn key_down_event(
-        &mut self,
-        ctx: &mut Context,
-        keycode: KeyCode,
-        keymods: KeyMods,
-        repeat: bool,
-        data: &mut Data,
-    ) {
+    /// Give the inputs to the top scene until one makes a transition
+    pub fn input(&mut self, ctx: &mut Context, data: &mut Data, inputs: Vec<Input>) {
+        for input in inputs {
+            if let Some(scene) = self.stack.last_mut() {
+                let tran = scene.input(ctx, input, data);
+                if let Transition::None = tran {
+                    continue;
+                }
+                self.do_transition(ctx, data, tran);
+                return;
+            }
+        }
+    }
+
+    pub fn text_input(&mut self, ctx: &mut Context, data: &mut Data, ch: char) {
         if let Some(scene) = self.stack.last_mut() {
-            let tran = scene.key_down_event(ctx, keycode, keymods, repeat, data);
-            self.do_transition(tran);
+            scene.text_input(ctx, ch, data);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Empty;
+
+    impl Scene for Empty {
+        fn update(&mut self, _ctx: &mut Context, _data: &mut Data) -> GameResult<Transition> {
+            Ok(Transition::None)
+        }
+
+        fn draw(&mut self, _ctx: &mut Context, _data: &mut Data) -> GameResult {
+            Ok(())
+        }
+
+        fn input(&mut self, _ctx: &mut Context, _input: Input, _data: &mut Data) -> Transition {
+            Transition::None
+        }
+    }
+
+    fn stack(depth: usize) -> SceneStack {
+        let mut stack = SceneStack::new();
+        for _ in 0..depth {
+            stack.stack.push(Box::new(Empty));
+        }
+        stack
+    }
+
+    #[test]
+    fn take_top_scenes() {
+        let mut stack = stack(3);
+        assert_eq!(stack.take(2).len(), 2);
+        assert_eq!(stack.stack.len(), 1);
+        assert!(stack.take(0).is_empty());
+        assert_eq!(stack.stack.len(), 1);
+    }
+
+    #[test]
+    fn take_more_than_the_depth() {
+        let mut stack = stack(2);
+        assert!(stack.take(3).is_empty());
+        assert_eq!(stack.stack.len(), 2);
+    }
+}
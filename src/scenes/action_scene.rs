@@ -1,10 +1,13 @@
 use super::target_scene::TargetScene;
+use crate::input::{Action, Input};
 use crate::scene::{Data, Scene, Transition};
-use ggez::event::KeyMods;
+use crate::ui::timeline::Timeline;
 use ggez::graphics;
+use ggez::nalgebra;
 use ggez::Context;
 use ggez::GameResult;
-use ggez::{event::KeyCode, nalgebra};
+use std::any::Any;
+use tower::core::Target;
 
 const BUTTON_W: i32 = 240;
 const BUTTON_H: i32 = 180;
@@ -14,7 +17,8 @@ pub struct ActionScene {
     character_id: i32,
     current_item: usize,
     action_ids: Vec<i32>,
-    waiting: bool,
+    /// Hidden while the target is picked
+    targeting: bool,
 
     action_texts: Vec<graphics::Text>,
     button_rectangle: graphics::Mesh,
@@ -31,16 +35,23 @@ impl ActionScene {
                 {
                     if let Some(a) = data.game.actions.get(&action_id) {
                         // draw buttom
-                        let s = format!(
-                        " Name: {} \n Description: {} \n Targe: {:?} \n Cost: Hp({}) / MP({}) \n Range: {} \n Cooldown: {}",
-                        a.name,
-                        a.description,
-                        a.target_kind,
-                        a.hp.unwrap_or_default(),
-                        a.mp.unwrap_or_default(),
-                        a.range,
-                        a.cooldown,
-                    );
+                        let localization = &data.game.localization;
+                        let s = localization.format(
+                            "ui.action.details",
+                            &[
+                                ("name", String::from(localization.get(&a.label))),
+                                (
+                                    "description",
+                                    String::from(localization.get(&a.description)),
+                                ),
+                                ("target", format!("{:?}", a.target_kind)),
+                                ("hp", a.hp.unwrap_or_default().to_string()),
+                                ("mp", a.mp.unwrap_or_default().to_string()),
+                                ("range", a.range.to_string()),
+                                ("cooldown", a.cooldown.to_string()),
+                                ("turn_time", a.turn_time.to_string()),
+                            ],
+                        );
                         let text = graphics::Text::new(s);
                         action_texts.push(text);
                         action_ids.push(action_id);
@@ -62,24 +73,36 @@ impl ActionScene {
             action_texts,
             action_ids,
             button_rectangle,
-            waiting: false,
+            targeting: false,
         }
     }
 }
 
 impl Scene for ActionScene {
-    fn update(&mut self, _ctx: &mut Context, data: &mut Data) -> GameResult<Transition> {
-        if self.waiting {
-            self.waiting = false;
-            if data.target_scene.finish {
-                return Ok(Transition::Pop);
-            }
-        }
+    fn update(&mut self, _ctx: &mut Context, _data: &mut Data) -> GameResult<Transition> {
         Ok(Transition::None)
     }
 
+    fn on_resume(&mut self, _ctx: &mut Context, _data: &mut Data) {
+        self.targeting = false;
+    }
+
+    fn on_result(
+        &mut self,
+        _ctx: &mut Context,
+        _data: &mut Data,
+        result: Box<dyn Any>,
+    ) -> Transition {
+        // The command was given
+        if result.is::<Target>() {
+            Transition::Pop
+        } else {
+            Transition::None
+        }
+    }
+
     fn draw(&mut self, ctx: &mut Context, _data: &mut Data) -> GameResult<()> {
-        if !self.waiting {
+        if !self.targeting {
             // draw action menu
             for (i, text) in self.action_texts.iter().enumerate() {
                 let x = (i / BUTTON_ROW * (BUTTON_W as usize)) as f32;
@@ -116,49 +139,65 @@ impl Scene for ActionScene {
         Ok(())
     }
 
-    fn key_down_event(
-        &mut self,
-        _ctx: &mut Context,
-        keycode: KeyCode,
-        _keymods: KeyMods,
-        _repeat: bool,
-        data: &mut Data,
-    ) -> Transition {
-        match keycode {
-            KeyCode::W => {
+    fn draw_top(&mut self, ctx: &mut Context, data: &mut Data) -> GameResult {
+        // Turn order if the selected action is used, the target is not known yet
+        if let Some(action_id) = self.action_ids.get(self.current_item) {
+            Timeline::draw_preview(
+                ctx,
+                &mut data.image_caches,
+                &data.game,
+                self.character_id,
+                *action_id,
+                &Target::None,
+                ggez::mint::Point2 { x: 720.0, y: 128.0 },
+            )?;
+        }
+        Ok(())
+    }
+
+    fn input(&mut self, _ctx: &mut Context, input: Input, data: &mut Data) -> Transition {
+        match input.action {
+            Action::Up => {
                 // Up
                 if self.current_item >= 1 {
                     self.current_item -= 1;
                 }
             }
-            KeyCode::A => {
+            Action::Left => {
                 // Left
                 if self.current_item >= BUTTON_ROW {
                     self.current_item -= BUTTON_ROW;
                 }
             }
-            KeyCode::S => {
+            Action::Down => {
                 // Down
                 if self.current_item + 1 < self.action_texts.len() {
                     self.current_item += 1;
                 }
             }
-            KeyCode::D => {
+            Action::Right => {
                 // Right
                 if self.current_item + BUTTON_ROW < self.action_texts.len() {
                     self.current_item += BUTTON_ROW;
                 }
             }
-            KeyCode::Q => {
+            Action::Cancel => {
                 // Cancel
-                data.action_scene.action_id = None;
                 return Transition::Pop;
             }
-            KeyCode::E => {
-                // Confirm
+            Action::Confirm => {
+                // Confirm, a click picks the clicked entry
+                if let Some(p) = input.pointer {
+                    let column = p.x as usize / BUTTON_W as usize;
+                    let row = p.y as usize / BUTTON_H as usize;
+                    let i = column * BUTTON_ROW + row;
+                    if p.x < 0.0 || p.y < 0.0 || row >= BUTTON_ROW || i >= self.action_ids.len() {
+                        return Transition::None;
+                    }
+                    self.current_item = i;
+                }
                 if let Some(action_ids) = self.action_ids.get(self.current_item) {
-                    self.waiting = true;
-                    data.target_scene.finish = false;
+                    self.targeting = true;
                     return Transition::Push(Box::new(TargetScene::new(
                         _ctx,
                         data,
@@ -167,7 +206,7 @@ impl Scene for ActionScene {
                     )));
                 }
             }
-            KeyCode::Escape => {
+            Action::Menu => {
                 // Open menu
             }
             _ => {}
@@ -0,0 +1,101 @@
+use ggez::Context;
+use ggez::GameResult;
+
+use ggez::graphics;
+
+use crate::input::{Action, Input};
+use crate::scene::{Data, Scene, Transition};
+use tower::core::Line;
+
+const LINES_PER_PAGE: usize = 16;
+const LINE_HEIGHT: f32 = 40.0;
+
+/// Past lines of the previous and current conversations
+pub struct BacklogScene {
+    /// Lines scrolled up from the newest
+    offset: usize,
+}
+
+impl BacklogScene {
+    pub fn new(_ctx: &mut Context, _data: &Data) -> Self {
+        Self { offset: 0 }
+    }
+
+    fn lines(data: &Data) -> Vec<&Line> {
+        let novel_system = &data.game.novel_system;
+        let current = novel_system.current.iter().flat_map(|c| c.history.iter());
+        novel_system.backlog.iter().chain(current).collect()
+    }
+}
+
+impl Scene for BacklogScene {
+    fn update(&mut self, _ctx: &mut Context, _data: &mut Data) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, data: &mut Data) -> GameResult<()> {
+        let bg = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new_i32(0, 0, 1280, 720),
+            graphics::Color::from_rgba(0, 0, 0, 223),
+        )?;
+        graphics::draw(ctx, &bg, graphics::DrawParam::new())?;
+
+        let lines = BacklogScene::lines(data);
+        let end = lines.len().saturating_sub(self.offset);
+        let start = end.saturating_sub(LINES_PER_PAGE);
+        for (i, line) in lines[start..end].iter().enumerate() {
+            let mut text = graphics::Text::default();
+            if let Some(speaker) = &line.speaker {
+                text.add(
+                    graphics::TextFragment::new(format!("{}: ", speaker))
+                        .color(graphics::Color::from_rgb(255, 255, 0)),
+                );
+            }
+            text.add(line.text.replace('\n', " "));
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam {
+                    dest: ggez::mint::Point2 {
+                        x: 16.0,
+                        y: 16.0 + i as f32 * LINE_HEIGHT,
+                    },
+                    ..Default::default()
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    fn input(&mut self, _ctx: &mut Context, input: Input, data: &mut Data) -> Transition {
+        let len = BacklogScene::lines(data).len();
+        match input.action {
+            Action::Up if self.offset + LINES_PER_PAGE < len => {
+                // Up
+                self.offset += 1;
+            }
+            Action::Left => {
+                // Page up
+                let max = len.saturating_sub(LINES_PER_PAGE);
+                self.offset = (self.offset + LINES_PER_PAGE).min(max);
+            }
+            Action::Down => {
+                // Down
+                self.offset = self.offset.saturating_sub(1);
+            }
+            Action::Right => {
+                // Page down
+                self.offset = self.offset.saturating_sub(LINES_PER_PAGE);
+            }
+            Action::Cancel | Action::Backlog | Action::Menu => {
+                // Cancel
+                return Transition::Pop;
+            }
+            _ => {}
+        }
+
+        Transition::None
+    }
+}
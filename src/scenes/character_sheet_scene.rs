@@ -0,0 +1,224 @@
+use ggez::Context;
+use ggez::GameResult;
+
+use ggez::graphics;
+
+use crate::input::{Action, Input};
+use crate::scene::{Data, Scene, Transition};
+use tower::core::AttributeKind;
+
+const LINES_PER_PAGE: usize = 16;
+const LINE_HEIGHT: f32 = 40.0;
+
+const ATTRIBUTES: [(AttributeKind, &str); 5] = [
+    (AttributeKind::Hp, "ui.sheet.hp"),
+    (AttributeKind::Mp, "ui.sheet.mp"),
+    (AttributeKind::Attack, "ui.sheet.attack"),
+    (AttributeKind::Defence, "ui.sheet.defence"),
+    (AttributeKind::Sanity, "ui.sheet.sanity"),
+];
+
+/// Attributes, level, currencies, equipment, effects and skills of a
+/// character
+pub struct CharacterSheetScene {
+    character_id: i32,
+    /// First line shown
+    offset: usize,
+}
+
+impl CharacterSheetScene {
+    pub fn new(_ctx: &mut Context, _data: &Data, character_id: i32) -> Self {
+        Self {
+            character_id,
+            offset: 0,
+        }
+    }
+
+    /// (indent, text, color) of every line
+    fn lines(&self, data: &Data) -> Vec<(f32, String, graphics::Color)> {
+        let game = &data.game;
+        let localization = &game.localization;
+        let title = graphics::Color::from_rgb(255, 255, 0);
+        let white = graphics::Color::from_rgb(255, 255, 255);
+        let grey = graphics::Color::from_rgb(127, 127, 127);
+        let mut lines = Vec::new();
+        let c = match game.characters.get(&self.character_id) {
+            Some(c) => c,
+            None => return lines,
+        };
+
+        lines.push((
+            0.0,
+            localization.format(
+                "ui.sheet.name",
+                &[
+                    ("name", String::from(localization.get(&c.name))),
+                    ("race", String::from(localization.get(c.race.key()))),
+                ],
+            ),
+            title,
+        ));
+        let level = match c.next_level_experience() {
+            Some(next) => localization.format(
+                "ui.sheet.level",
+                &[
+                    ("level", c.level.to_string()),
+                    ("experience", c.experience.to_string()),
+                    ("next", next.to_string()),
+                ],
+            ),
+            None => localization.format(
+                "ui.sheet.max_level",
+                &[
+                    ("level", c.level.to_string()),
+                    ("experience", c.experience.to_string()),
+                ],
+            ),
+        };
+        lines.push((32.0, level, white));
+        let currencies = localization.format(
+            "ui.sheet.currencies",
+            &[("gold", c.gold.to_string()), ("wood", c.wood.to_string())],
+        );
+        lines.push((32.0, currencies, white));
+
+        let text = localization.get("ui.sheet.attributes");
+        lines.push((0.0, String::from(text), title));
+        for (kind, key) in ATTRIBUTES.iter() {
+            let a = c.attribute(*kind);
+            let text = localization.format(
+                key,
+                &[
+                    ("current", a.current().to_string()),
+                    ("max", a.max().to_string()),
+                    ("base", a.base.to_string()),
+                    ("modifier", format!("{:+}", a.modifier)),
+                    ("drain", a.drain.to_string()),
+                ],
+            );
+            lines.push((32.0, text, white));
+        }
+
+        let text = localization.get("ui.sheet.equipment");
+        lines.push((0.0, String::from(text), title));
+        for item in c.equipment.iter().filter_map(|id| game.items.get(id)) {
+            lines.push((32.0, String::from(localization.get(&item.name)), white));
+        }
+        if c.equipment.is_empty() {
+            let text = localization.get("ui.sheet.none");
+            lines.push((32.0, String::from(text), grey));
+        }
+
+        let text = localization.get("ui.sheet.effects");
+        lines.push((0.0, String::from(text), title));
+        let cc = &c.crowd_controls;
+        let effects = [
+            ("ui.sheet.stun", cc.stun),
+            ("ui.sheet.charm", cc.charm),
+            ("ui.sheet.shock", cc.shock),
+            ("ui.sheet.poison", cc.poison),
+            ("ui.sheet.sleep", cc.sleep),
+            ("ui.sheet.root", cc.root),
+            ("ui.sheet.silent", cc.silent),
+        ];
+        let mut active = false;
+        for (key, turns) in effects.iter().filter(|(_, turns)| *turns > 0) {
+            let text = localization.format(key, &[("turns", turns.to_string())]);
+            lines.push((32.0, text, white));
+            active = true;
+        }
+        if cc.shocked {
+            let text = localization.get("ui.sheet.shocked");
+            lines.push((32.0, String::from(text), white));
+            active = true;
+        }
+        if !active {
+            let text = localization.get("ui.sheet.none");
+            lines.push((32.0, String::from(text), grey));
+        }
+
+        let text = localization.get("ui.sheet.skills");
+        lines.push((0.0, String::from(text), title));
+        for ca in c
+            .character_action_ids
+            .iter()
+            .filter_map(|id| game.character_actions.get(id))
+        {
+            let action = match game.actions.get(&ca.action_id) {
+                Some(action) => action,
+                None => continue,
+            };
+            let text = localization.format(
+                "ui.sheet.skill",
+                &[
+                    ("label", String::from(localization.get(&action.label))),
+                    ("cooldown", ca.cooldown.to_string()),
+                    ("max_cooldown", action.cooldown.to_string()),
+                    ("mastery", ca.exp.to_string()),
+                ],
+            );
+            let color = if ca.cooldown > 0 { grey } else { white };
+            lines.push((32.0, text, color));
+        }
+        lines
+    }
+}
+
+impl Scene for CharacterSheetScene {
+    fn update(&mut self, _ctx: &mut Context, _data: &mut Data) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, data: &mut Data) -> GameResult<()> {
+        let bg = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new_i32(0, 0, 1280, 720),
+            graphics::Color::from_rgba(0, 0, 0, 223),
+        )?;
+        graphics::draw(ctx, &bg, graphics::DrawParam::new())?;
+
+        let lines = self.lines(data);
+        for (i, (indent, line, color)) in lines
+            .into_iter()
+            .skip(self.offset)
+            .take(LINES_PER_PAGE)
+            .enumerate()
+        {
+            let text = graphics::Text::new(graphics::TextFragment::new(line).color(color));
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam {
+                    dest: ggez::mint::Point2 {
+                        x: 16.0 + indent,
+                        y: 16.0 + i as f32 * LINE_HEIGHT,
+                    },
+                    ..Default::default()
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    fn input(&mut self, _ctx: &mut Context, input: Input, data: &mut Data) -> Transition {
+        let len = self.lines(data).len();
+        match input.action {
+            Action::Up => {
+                // Up
+                self.offset = self.offset.saturating_sub(1);
+            }
+            Action::Down if self.offset + LINES_PER_PAGE < len => {
+                // Down
+                self.offset += 1;
+            }
+            Action::Cancel | Action::CharacterSheet | Action::Menu => {
+                // Cancel
+                return Transition::Pop;
+            }
+            _ => {}
+        }
+
+        Transition::None
+    }
+}
@@ -0,0 +1,370 @@
+use ggez::graphics;
+use ggez::mint::Point2;
+use ggez::timer;
+use ggez::Context;
+use ggez::GameResult;
+use tower::core::{AttributePoints, CharacterCreation, Race, MAX_NAME_LEN};
+
+use super::level_scene::LevelScene;
+use crate::input::{Action, Input};
+use crate::scene::{Data, Scene, Transition};
+use crate::storage;
+use crate::ui::menu::Menu;
+
+const NAME: usize = 0;
+const RACE: usize = 1;
+const CLASS: usize = 2;
+const PORTRAIT: usize = 3;
+const HP: usize = 4;
+const MP: usize = 5;
+const ATTACK: usize = 6;
+const DEFENCE: usize = 7;
+const SANITY: usize = 8;
+const START: usize = 9;
+
+const PORTRAIT_SIZE: f32 = 256.0;
+
+/// Name, race, class, portrait and attribute points of the player character,
+/// then starts a new game with it
+pub struct CreationScene {
+    creation: CharacterCreation,
+    class_ids: Vec<i32>,
+    character_sprite_ids: Vec<i32>,
+    /// Tick the name edition started at, the key starting it types nothing
+    editing: Option<usize>,
+    menu: Menu,
+}
+
+impl CreationScene {
+    pub fn new(ctx: &mut Context, data: &Data) -> Self {
+        let game = &data.game;
+        let mut class_ids: Vec<i32> = game.player_classes.keys().copied().collect();
+        class_ids.sort_unstable();
+        let mut character_sprite_ids: Vec<i32> = game.character_sprites.keys().copied().collect();
+        character_sprite_ids.sort_unstable();
+        let creation = CharacterCreation {
+            name: String::new(),
+            race: Race::Human,
+            class_id: class_ids.first().copied().unwrap_or_default(),
+            character_sprite_id: character_sprite_ids.first().copied().unwrap_or_default(),
+            points: AttributePoints::default(),
+        };
+        let mut scene = Self {
+            creation,
+            class_ids,
+            character_sprite_ids,
+            editing: Some(timer::ticks(ctx)),
+            menu: Menu::new(Vec::new(), Point2 { x: 120.0, y: 120.0 }),
+        };
+        scene.refresh(data);
+        scene
+    }
+
+    /// Rebuild the entries after a change
+    fn refresh(&mut self, data: &Data) {
+        let game = &data.game;
+        let localization = &game.localization;
+        let creation = &self.creation;
+        let class = game.player_classes.get(&creation.class_id);
+        let mut name = creation.name.clone();
+        if self.editing.is_some() {
+            name.push('_');
+        }
+        let portrait = self
+            .character_sprite_ids
+            .iter()
+            .position(|id| *id == creation.character_sprite_id)
+            .map(|i| i + 1)
+            .unwrap_or_default();
+        let attribute = |key, base: Option<i32>, points: i32| {
+            let value = base.unwrap_or_default() + points;
+            localization.format(key, &[("value", value.to_string())])
+        };
+        let items = vec![
+            (
+                localization.format("ui.create.name", &[("name", name)]),
+                true,
+            ),
+            (
+                localization.format(
+                    "ui.create.race",
+                    &[("race", String::from(localization.get(creation.race.key())))],
+                ),
+                true,
+            ),
+            (
+                localization.format(
+                    "ui.create.class",
+                    &[(
+                        "class",
+                        String::from(class.map(|c| localization.get(&c.name)).unwrap_or("")),
+                    )],
+                ),
+                true,
+            ),
+            (
+                localization.format(
+                    "ui.create.portrait",
+                    &[
+                        ("index", portrait.to_string()),
+                        ("count", self.character_sprite_ids.len().to_string()),
+                    ],
+                ),
+                true,
+            ),
+            (
+                attribute("ui.create.hp", class.map(|c| c.hp), creation.points.hp),
+                true,
+            ),
+            (
+                attribute("ui.create.mp", class.map(|c| c.mp), creation.points.mp),
+                true,
+            ),
+            (
+                attribute(
+                    "ui.create.attack",
+                    class.map(|c| c.attack),
+                    creation.points.attack,
+                ),
+                true,
+            ),
+            (
+                attribute(
+                    "ui.create.defence",
+                    class.map(|c| c.defence),
+                    creation.points.defence,
+                ),
+                true,
+            ),
+            (
+                attribute(
+                    "ui.create.sanity",
+                    class.map(|c| c.sanity),
+                    creation.points.sanity,
+                ),
+                true,
+            ),
+            (
+                String::from(localization.get("ui.create.start")),
+                self.editing.is_none() && creation.validate(game).is_ok(),
+            ),
+        ];
+        self.menu.items = items;
+    }
+
+    /// Points of the attribute of the entry
+    fn points(&mut self, item: usize) -> Option<&mut i32> {
+        let points = &mut self.creation.points;
+        match item {
+            HP => Some(&mut points.hp),
+            MP => Some(&mut points.mp),
+            ATTACK => Some(&mut points.attack),
+            DEFENCE => Some(&mut points.defence),
+            SANITY => Some(&mut points.sanity),
+            _ => None,
+        }
+    }
+
+    /// Next or previous value of the entry
+    fn change(&mut self, data: &Data, forward: bool) {
+        let step = |i: usize, len: usize| {
+            if forward {
+                (i + 1) % len
+            } else {
+                (i + len - 1) % len
+            }
+        };
+        let item = self.menu.current;
+        match item {
+            RACE => {
+                let len = Race::ALL.len();
+                let i = Race::ALL.iter().position(|r| *r == self.creation.race);
+                self.creation.race = Race::ALL[step(i.unwrap_or_default(), len)];
+            }
+            CLASS if !self.class_ids.is_empty() => {
+                let len = self.class_ids.len();
+                let i = self
+                    .class_ids
+                    .iter()
+                    .position(|id| *id == self.creation.class_id);
+                self.creation.class_id = self.class_ids[step(i.unwrap_or_default(), len)];
+                self.creation.points = AttributePoints::default();
+            }
+            PORTRAIT if !self.character_sprite_ids.is_empty() => {
+                let len = self.character_sprite_ids.len();
+                let i = self
+                    .character_sprite_ids
+                    .iter()
+                    .position(|id| *id == self.creation.character_sprite_id);
+                self.creation.character_sprite_id =
+                    self.character_sprite_ids[step(i.unwrap_or_default(), len)];
+            }
+            _ => {
+                let available = data
+                    .game
+                    .player_classes
+                    .get(&self.creation.class_id)
+                    .map(|c| c.points)
+                    .unwrap_or_default();
+                let left = available - self.creation.points.total();
+                if let Some(points) = self.points(item) {
+                    if forward && left > 0 {
+                        *points += 1;
+                    } else if !forward && *points > 0 {
+                        *points -= 1;
+                    }
+                }
+            }
+        }
+        self.refresh(data);
+    }
+
+    fn start(&mut self, ctx: &mut Context, data: &mut Data) -> Transition {
+        let mut game = storage::load_game(ctx);
+        match game.new_game(&self.creation) {
+            Ok(_) => {
+                storage::replace_game(&mut data.game, game);
+                Transition::Replace(Box::new(LevelScene::new(ctx, data)))
+            }
+            Err(e) => {
+                log::error!("Cannot create the character: {}", e);
+                Transition::None
+            }
+        }
+    }
+}
+
+impl Scene for CreationScene {
+    fn update(&mut self, _ctx: &mut Context, _data: &mut Data) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, data: &mut Data) -> GameResult<()> {
+        graphics::clear(ctx, graphics::BLACK);
+        let localization = &data.game.localization;
+        let title = graphics::Text::new(localization.get("ui.create.title"));
+        graphics::draw(
+            ctx,
+            &title,
+            graphics::DrawParam::new().dest(Point2 { x: 136.0, y: 64.0 }),
+        )?;
+        self.menu.draw(ctx)?;
+
+        let class = data.game.player_classes.get(&self.creation.class_id);
+        let available = class.map(|c| c.points).unwrap_or_default();
+        let mut text = graphics::Text::new(localization.format(
+            "ui.create.points",
+            &[(
+                "points",
+                (available - self.creation.points.total()).to_string(),
+            )],
+        ));
+        text.add("\n\n");
+        if let Some(class) = class {
+            text.add(localization.get(&class.description));
+        }
+        text.set_bounds(Point2 { x: 360.0, y: 240.0 }, graphics::Align::Left);
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new().dest(Point2 { x: 800.0, y: 400.0 }),
+        )?;
+        let help = graphics::Text::new(localization.get("ui.create.help"));
+        graphics::draw(
+            ctx,
+            &help,
+            graphics::DrawParam::new().dest(Point2 { x: 136.0, y: 656.0 }),
+        )?;
+
+        let path = data
+            .game
+            .character_sprites
+            .get(&self.creation.character_sprite_id)
+            .map(|s| s.portrait.clone().unwrap_or_else(|| s.avatar.clone()));
+        if let Some(path) = path {
+            let image = data.image_caches.get(ctx, &path)?;
+            let scale = PORTRAIT_SIZE / image.width().max(image.height()).max(1) as f32;
+            graphics::draw(
+                ctx,
+                image,
+                graphics::DrawParam::new()
+                    .dest(Point2 { x: 800.0, y: 120.0 })
+                    .scale([scale, scale]),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn input(&mut self, ctx: &mut Context, input: Input, data: &mut Data) -> Transition {
+        if self.editing.is_some() {
+            // Keys type the name, a click elsewhere stops typing
+            if let (Action::Confirm, Some(p)) = (input.action, input.pointer) {
+                if !self.menu.point(p) || self.menu.current != NAME {
+                    self.editing = None;
+                    self.refresh(data);
+                }
+            }
+            return Transition::None;
+        }
+        match input.action {
+            Action::Up => {
+                // Up
+                self.menu.up();
+            }
+            Action::Down => {
+                // Down
+                self.menu.down();
+            }
+            Action::Left => {
+                // Previous value
+                self.change(data, false);
+            }
+            Action::Right => {
+                // Next value
+                self.change(data, true);
+            }
+            Action::Cancel | Action::Menu => {
+                // Back to the title
+                return Transition::Pop;
+            }
+            Action::Confirm => {
+                // Confirm
+                if let Some(p) = input.pointer {
+                    if !self.menu.point(p) {
+                        return Transition::None;
+                    }
+                }
+                match self.menu.selected() {
+                    Some(NAME) => {
+                        self.editing = Some(timer::ticks(ctx));
+                        self.refresh(data);
+                    }
+                    Some(START) => return self.start(ctx, data),
+                    Some(_) => self.change(data, true),
+                    None => {}
+                }
+            }
+            _ => {}
+        }
+
+        Transition::None
+    }
+
+    fn text_input(&mut self, ctx: &mut Context, ch: char, data: &mut Data) {
+        match self.editing {
+            Some(tick) if tick != timer::ticks(ctx) => {}
+            _ => return,
+        }
+        let name = &mut self.creation.name;
+        match ch {
+            '\r' | '\n' => self.editing = None,
+            // Backspace, delete on macOS
+            '\u{8}' | '\u{7f}' => {
+                name.pop();
+            }
+            ch if !ch.is_control() && name.chars().count() < MAX_NAME_LEN => name.push(ch),
+            _ => {}
+        }
+        self.refresh(data);
+    }
+}
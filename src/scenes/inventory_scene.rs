@@ -0,0 +1,257 @@
+use ggez::graphics;
+use ggez::mint::Point2;
+use ggez::Context;
+use ggez::GameResult;
+use tower::core::{InventorySystem, ItemKind};
+
+use crate::input::{Action, Input};
+use crate::scene::{Data, Scene, Transition};
+
+const VISIBLE_ENTRIES: usize = 12;
+const ENTRY_WIDTH: f32 = 480.0;
+const ENTRY_HEIGHT: f32 = 48.0;
+const TOP: f32 = 64.0;
+
+enum Entry {
+    Equipped(i32),
+    /// Held(item_id, quantity)
+    Held(i32, i32),
+}
+
+impl Entry {
+    fn item_id(&self) -> i32 {
+        match self {
+            Entry::Equipped(id) | Entry::Held(id, _) => *id,
+        }
+    }
+}
+
+/// Equipped and held items of a character, to use, equip and drop them
+pub struct InventoryScene {
+    character_id: i32,
+    current_item: usize,
+    scroll: usize,
+    /// Localization key of the result of the last use
+    message: Option<&'static str>,
+}
+
+impl InventoryScene {
+    pub fn new(_ctx: &mut Context, _data: &Data, character_id: i32) -> Self {
+        Self {
+            character_id,
+            current_item: 0,
+            scroll: 0,
+            message: None,
+        }
+    }
+
+    /// Equipped items first, then the held ones
+    fn entries(&self, data: &Data) -> Vec<Entry> {
+        let c = match data.game.characters.get(&self.character_id) {
+            Some(c) => c,
+            None => return Vec::new(),
+        };
+        let equipped = c.equipment.iter().map(|id| Entry::Equipped(*id));
+        let held = c
+            .items
+            .iter()
+            .map(|ci| Entry::Held(ci.item_id, ci.quantity));
+        equipped.chain(held).collect()
+    }
+
+    /// Keep the selection in the list and visible
+    fn clamp(&mut self, len: usize) {
+        self.current_item = self.current_item.min(len.saturating_sub(1));
+        if self.current_item < self.scroll {
+            self.scroll = self.current_item;
+        } else if self.current_item >= self.scroll + VISIBLE_ENTRIES {
+            self.scroll = self.current_item + 1 - VISIBLE_ENTRIES;
+        }
+    }
+
+    fn confirm(&mut self, data: &mut Data, entry: &Entry) {
+        let game = &mut data.game;
+        let (result, done) = match entry {
+            Entry::Equipped(id) => (
+                InventorySystem::unequip(game, self.character_id, *id),
+                "ui.inventory.unequipped",
+            ),
+            Entry::Held(id, _) => {
+                let done = match game.items.get(id).map(|i| &i.item_kind) {
+                    Some(ItemKind::Potion { .. }) => "ui.inventory.drunk",
+                    Some(ItemKind::ActionBook(_)) => "ui.inventory.learned",
+                    _ => "ui.inventory.equipped",
+                };
+                (
+                    InventorySystem::use_item(game, self.character_id, *id),
+                    done,
+                )
+            }
+        };
+        self.message = match result {
+            Ok(()) => Some(done),
+            Err(e) => {
+                log::info!("{}", e);
+                Some("ui.inventory.failed")
+            }
+        };
+    }
+}
+
+impl Scene for InventoryScene {
+    fn update(&mut self, _ctx: &mut Context, _data: &mut Data) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, data: &mut Data) -> GameResult<()> {
+        let bg = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new_i32(0, 0, 1280, 720),
+            graphics::Color::from_rgba(0, 0, 0, 223),
+        )?;
+        graphics::draw(ctx, &bg, graphics::DrawParam::new())?;
+        let highlight = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(0.0, 0.0, ENTRY_WIDTH, ENTRY_HEIGHT),
+            graphics::Color::from_rgba(0, 127, 127, 191),
+        )?;
+
+        let localization = &data.game.localization;
+        let title = graphics::Text::new(localization.get("ui.inventory.title"));
+        graphics::draw(
+            ctx,
+            &title,
+            graphics::DrawParam::new().dest(Point2 { x: 32.0, y: 24.0 }),
+        )?;
+
+        let entries = self.entries(data);
+        if entries.is_empty() {
+            let text = graphics::Text::new(localization.get("ui.inventory.empty"));
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam::new().dest(Point2 {
+                    x: 32.0,
+                    y: TOP + 12.0,
+                }),
+            )?;
+        }
+        for (i, entry) in entries
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(VISIBLE_ENTRIES)
+        {
+            let y = TOP + (i - self.scroll) as f32 * ENTRY_HEIGHT;
+            if i == self.current_item {
+                graphics::draw(
+                    ctx,
+                    &highlight,
+                    graphics::DrawParam::new().dest(Point2 { x: 16.0, y }),
+                )?;
+            }
+            let name = data
+                .game
+                .items
+                .get(&entry.item_id())
+                .map(|item| localization.get(&item.name))
+                .unwrap_or("?");
+            let line = match entry {
+                Entry::Equipped(_) => localization.format(
+                    "ui.inventory.equipped_item",
+                    &[("name", String::from(name))],
+                ),
+                Entry::Held(_, quantity) => localization.format(
+                    "ui.inventory.held_item",
+                    &[
+                        ("name", String::from(name)),
+                        ("quantity", quantity.to_string()),
+                    ],
+                ),
+            };
+            let text = graphics::Text::new(line);
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam::new().dest(Point2 {
+                    x: 32.0,
+                    y: y + 12.0,
+                }),
+            )?;
+        }
+
+        // Details of the selection
+        let mut text = graphics::Text::default();
+        if let Some(item) = entries
+            .get(self.current_item)
+            .and_then(|e| data.game.items.get(&e.item_id()))
+        {
+            text.add(localization.get(&item.description));
+            text.add("\n\n");
+        }
+        text.add(localization.get("ui.inventory.help"));
+        if let Some(message) = self.message {
+            text.add("\n\n");
+            text.add(localization.get(message));
+        }
+        text.set_bounds(Point2 { x: 640.0, y: 600.0 }, graphics::Align::Left);
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new().dest(Point2 { x: 560.0, y: TOP }),
+        )?;
+        Ok(())
+    }
+
+    fn input(&mut self, _ctx: &mut Context, input: Input, data: &mut Data) -> Transition {
+        let entries = self.entries(data);
+        match input.action {
+            Action::Up => {
+                // Up
+                self.current_item = self.current_item.saturating_sub(1);
+            }
+            Action::Down => {
+                // Down
+                self.current_item += 1;
+            }
+            Action::Confirm => {
+                // Use, equip or unequip, a click picks the clicked entry
+                if let Some(p) = input.pointer {
+                    let row = ((p.y - TOP) / ENTRY_HEIGHT).floor();
+                    let i = self.scroll + row as usize;
+                    if p.x < 16.0 || p.x >= 16.0 + ENTRY_WIDTH || row < 0.0 || i >= entries.len() {
+                        return Transition::None;
+                    }
+                    self.current_item = i;
+                }
+                if let Some(entry) = entries.get(self.current_item) {
+                    self.confirm(data, entry);
+                }
+            }
+            Action::Drop => {
+                // Drop one
+                if let Some(Entry::Held(id, _)) = entries.get(self.current_item) {
+                    self.message =
+                        match InventorySystem::drop_item(&mut data.game, self.character_id, *id) {
+                            Ok(()) => Some("ui.inventory.dropped"),
+                            Err(e) => {
+                                log::info!("{}", e);
+                                Some("ui.inventory.failed")
+                            }
+                        };
+                }
+            }
+            Action::Cancel | Action::Inventory | Action::Menu => {
+                // Cancel
+                return Transition::Pop;
+            }
+            _ => {}
+        }
+        let len = self.entries(data).len();
+        self.clamp(len);
+
+        Transition::None
+    }
+}
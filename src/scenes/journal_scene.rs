@@ -0,0 +1,139 @@
+use ggez::Context;
+use ggez::GameResult;
+
+use ggez::graphics;
+
+use crate::input::{Action, Input};
+use crate::scene::{Data, Scene, Transition};
+
+const LINES_PER_PAGE: usize = 16;
+const LINE_HEIGHT: f32 = 40.0;
+
+/// Active quests with their objectives, then completed quests
+pub struct JournalScene {
+    /// First line shown
+    offset: usize,
+}
+
+impl JournalScene {
+    pub fn new(_ctx: &mut Context, _data: &Data) -> Self {
+        Self { offset: 0 }
+    }
+
+    /// (indent, text, color) of every line
+    fn lines(data: &Data) -> Vec<(f32, String, graphics::Color)> {
+        let game = &data.game;
+        let localization = &game.localization;
+        let title = graphics::Color::from_rgb(255, 255, 0);
+        let white = graphics::Color::from_rgb(255, 255, 255);
+        let grey = graphics::Color::from_rgb(127, 127, 127);
+        let mut lines = Vec::new();
+        if !game.quest_system.active.is_empty() {
+            let text = localization.get("ui.journal.active");
+            lines.push((0.0, String::from(text), title));
+        }
+        for active in &game.quest_system.active {
+            let quest = match game.quests.get(&active.quest_id) {
+                Some(quest) => quest,
+                None => continue,
+            };
+            lines.push((32.0, String::from(localization.get(&quest.name)), white));
+            if let Some(stage) = quest.stages.get(active.stage) {
+                let text = localization.get(&stage.description);
+                lines.push((64.0, String::from(text), white));
+                for (objective, progress) in stage.objectives.iter().zip(&active.progress) {
+                    let text = localization.format(
+                        "ui.journal.objective",
+                        &[
+                            (
+                                "description",
+                                String::from(localization.get(&objective.description)),
+                            ),
+                            ("progress", progress.min(&objective.count).to_string()),
+                            ("count", objective.count.to_string()),
+                        ],
+                    );
+                    let color = if *progress >= objective.count {
+                        grey
+                    } else {
+                        white
+                    };
+                    lines.push((96.0, text, color));
+                }
+            }
+        }
+        if !game.quest_system.completed.is_empty() {
+            let text = localization.get("ui.journal.completed");
+            lines.push((0.0, String::from(text), title));
+        }
+        for quest_id in &game.quest_system.completed {
+            if let Some(quest) = game.quests.get(quest_id) {
+                lines.push((32.0, String::from(localization.get(&quest.name)), grey));
+            }
+        }
+        if lines.is_empty() {
+            let text = localization.get("ui.journal.empty");
+            lines.push((0.0, String::from(text), grey));
+        }
+        lines
+    }
+}
+
+impl Scene for JournalScene {
+    fn update(&mut self, _ctx: &mut Context, _data: &mut Data) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, data: &mut Data) -> GameResult<()> {
+        let bg = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new_i32(0, 0, 1280, 720),
+            graphics::Color::from_rgba(0, 0, 0, 223),
+        )?;
+        graphics::draw(ctx, &bg, graphics::DrawParam::new())?;
+
+        let lines = JournalScene::lines(data);
+        for (i, (indent, line, color)) in lines
+            .into_iter()
+            .skip(self.offset)
+            .take(LINES_PER_PAGE)
+            .enumerate()
+        {
+            let text = graphics::Text::new(graphics::TextFragment::new(line).color(color));
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam {
+                    dest: ggez::mint::Point2 {
+                        x: 16.0 + indent,
+                        y: 16.0 + i as f32 * LINE_HEIGHT,
+                    },
+                    ..Default::default()
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    fn input(&mut self, _ctx: &mut Context, input: Input, data: &mut Data) -> Transition {
+        let len = JournalScene::lines(data).len();
+        match input.action {
+            Action::Up => {
+                // Up
+                self.offset = self.offset.saturating_sub(1);
+            }
+            Action::Down if self.offset + LINES_PER_PAGE < len => {
+                // Down
+                self.offset += 1;
+            }
+            Action::Cancel | Action::Journal | Action::Menu => {
+                // Cancel
+                return Transition::Pop;
+            }
+            _ => {}
+        }
+
+        Transition::None
+    }
+}
@@ -1,25 +1,38 @@
 use crate::{
     rendering::{Rendering, RenderingParam},
-    ui::stats::Stats,
+    ui::{
+        combat_effects::CombatEffects, combat_log::CombatLog, minimap::Minimap, stats::Stats,
+        timeline::Timeline,
+    },
     ActionScene,
 };
-use ggez::event::KeyCode;
 use ggez::graphics;
+use ggez::timer;
 use ggez::Context;
 use ggez::GameResult;
-use ggez::{event::KeyMods, timer};
 use tower::core::{Animation, Character, TileKind, TurnKind};
 
+use crate::input::{Action, Input};
 use crate::scene::{Data, Scene, Transition};
 
-use super::{novel_scene::NovelScene, pause_scene::PauseScene};
+use super::{
+    character_sheet_scene::CharacterSheetScene, inventory_scene::InventoryScene,
+    journal_scene::JournalScene, map_scene::MapScene, novel_scene::NovelScene,
+    pause_scene::PauseScene,
+};
 
-pub struct LevelScene {}
+pub struct LevelScene {
+    combat_log: CombatLog,
+    combat_effects: CombatEffects,
+}
 
 impl LevelScene {
-    pub fn new(_ctx: &mut Context, _data: &mut Data) -> Self {
+    pub fn new(_ctx: &mut Context, data: &mut Data) -> Self {
         // Load/create resources such as images here.
-        Self {}
+        Self {
+            combat_log: CombatLog::default(),
+            combat_effects: CombatEffects::new(&data.game),
+        }
     }
 }
 
@@ -29,7 +42,9 @@ impl Scene for LevelScene {
             return Ok(Transition::Push(Box::new(NovelScene::new(ctx, data))));
         }
 
-        data.game.update(timer::delta(&ctx).as_secs_f32());
+        let delta = timer::delta(&ctx).as_secs_f32();
+        data.game.update(delta);
+        self.combat_effects.update(&data.game, delta);
 
         Ok(Transition::None)
     }
@@ -38,11 +53,20 @@ impl Scene for LevelScene {
         let camera = &data.game.camera;
         if let Some(character) = data.game.characters.get(&camera.character_id) {
             // Draw Tiles
-            if let Some(map) = data.game.tile_maps.get(&character.position.map_id) {
-                let start_y = character.position.y - camera.extend;
-                let end_y = character.position.y + camera.extend;
-                let start_x = character.position.x - camera.extend;
-                let end_x = character.position.x + camera.extend;
+            let (map_id, center_x, center_y) = data
+                .game
+                .camera_center()
+                .map(|(map_id, x, y)| (map_id, x.round() as i32, y.round() as i32))
+                .unwrap_or((
+                    character.position.map_id,
+                    character.position.x,
+                    character.position.y,
+                ));
+            if let Some(map) = data.game.tile_maps.get(&map_id) {
+                let start_y = center_y - camera.extend;
+                let end_y = center_y + camera.extend;
+                let start_x = center_x - camera.extend;
+                let end_x = center_x + camera.extend;
 
                 for y in start_y..=end_y {
                     if let Some(rows) = map.tiles.get(y as usize) {
@@ -55,11 +79,7 @@ impl Scene for LevelScene {
                                                 let image =
                                                     data.image_caches.get(ctx, &sheet.file_path)?;
                                                 RenderingParam::default()
-                                                    .dest(
-                                                        character.position.map_id,
-                                                        x as f32,
-                                                        y as f32,
-                                                    )
+                                                    .dest(map_id, x as f32, y as f32)
                                                     .rect(
                                                         (sheet.tile_width * tile_x) as f32,
                                                         (sheet.tile_height * tile_y) as f32,
@@ -73,7 +93,7 @@ impl Scene for LevelScene {
                                         TileKind::Image(s) => {
                                             let image = data.image_caches.get(ctx, s)?;
                                             RenderingParam::default()
-                                                .dest(character.position.map_id, x as f32, y as f32)
+                                                .dest(map_id, x as f32, y as f32)
                                                 .target_size(64 as f32, 64 as f32)
                                                 .draw_image(&data.game, image, ctx)?;
                                         }
@@ -104,10 +124,15 @@ impl Scene for LevelScene {
                     None
                 }
             };
+            self.combat_effects
+                .draw_fades(ctx, &mut data.image_caches, &data.game)?;
             for (id, character) in data.game.characters.iter() {
                 if character.hidden {
                     continue;
                 }
+                let (shake, flash) = self.combat_effects.hit(*id).unwrap_or((0.0, 0));
+                let x = character.position.x as f32 + character.offset_x + shake;
+                let y = character.position.y as f32 + character.offset_y;
                 let avatar = &data
                     .game
                     .character_sprites
@@ -116,13 +141,19 @@ impl Scene for LevelScene {
                     .avatar;
                 let image = data.image_caches.get(ctx, avatar)?;
                 RenderingParam::default()
-                    .dest(
-                        character.position.map_id,
-                        character.position.x as f32 + character.offset_x,
-                        character.position.y as f32 + character.offset_y,
-                    )
+                    .dest(character.position.map_id, x, y)
                     .target_size(64 as f32, 64 as f32)
                     .draw_image(&data.game, &image, ctx)?;
+                if flash > 0 {
+                    CombatEffects::draw_hit(
+                        ctx,
+                        &data.game,
+                        character.position.map_id,
+                        x,
+                        y,
+                        flash,
+                    )?;
+                }
 
                 if current_id == Some(*id) {
                     // Draw border
@@ -176,10 +207,13 @@ impl Scene for LevelScene {
                 }
             }
 
+            self.combat_effects.draw_texts(ctx, &data.game)?;
+
+            Rendering::draw_fade(ctx, &data.game)?;
             Rendering::draw_border(ctx, &data.game)?;
 
             // Draw ui, show characer hp, mp, etc...
-            let stats_ui = Stats::new(ctx, character)?;
+            let stats_ui = Stats::new(ctx, character, &data.game.localization)?;
             stats_ui.draw_canvas(ctx)?;
             graphics::draw(
                 ctx,
@@ -189,22 +223,40 @@ impl Scene for LevelScene {
                     ..Default::default()
                 },
             )?;
+            Minimap::draw(
+                ctx,
+                &data.game,
+                graphics::Rect::new(720.0, 240.0, 256.0, 256.0),
+                16.0,
+            )?;
+            self.combat_log
+                .draw(ctx, &data.game, ggez::mint::Point2 { x: 720.0, y: 504.0 })?;
         }
 
         Ok(())
     }
 
-    fn key_down_event(
-        &mut self,
-        ctx: &mut Context,
-        keycode: KeyCode,
-        _keymods: KeyMods,
-        _repeat: bool,
-        data: &mut Data,
-    ) -> Transition {
+    fn draw_top(&mut self, ctx: &mut Context, data: &mut Data) -> GameResult {
+        // The scenes above show their own turn order
+        Timeline::draw(
+            ctx,
+            &mut data.image_caches,
+            &data.game,
+            data.game.localization.get("ui.timeline.title"),
+            None,
+            ggez::mint::Point2 { x: 720.0, y: 128.0 },
+        )
+    }
+
+    fn input(&mut self, ctx: &mut Context, input: Input, data: &mut Data) -> Transition {
+        match input.action {
+            Action::ScrollUp => self.combat_log.scroll_up(&data.game),
+            Action::ScrollDown => self.combat_log.scroll_down(),
+            _ => {}
+        }
         if data.game.turn_system.waiting_input {
-            match keycode {
-                KeyCode::W => {
+            match input.action {
+                Action::Up => {
                     // Up
                     if let Some(t) = &data.game.turn_system.current_turn {
                         if let TurnKind::Character { character_id } = &t.kind {
@@ -217,7 +269,7 @@ impl Scene for LevelScene {
                         }
                     }
                 }
-                KeyCode::A => {
+                Action::Left => {
                     // Left
                     if let Some(t) = &data.game.turn_system.current_turn {
                         if let TurnKind::Character { character_id } = &t.kind {
@@ -230,7 +282,7 @@ impl Scene for LevelScene {
                         }
                     }
                 }
-                KeyCode::S => {
+                Action::Down => {
                     // Down
                     if let Some(t) = &data.game.turn_system.current_turn {
                         if let TurnKind::Character { character_id } = &t.kind {
@@ -243,7 +295,7 @@ impl Scene for LevelScene {
                         }
                     }
                 }
-                KeyCode::D => {
+                Action::Right => {
                     // Right
                     if let Some(t) = &data.game.turn_system.current_turn {
                         if let TurnKind::Character { character_id } = &t.kind {
@@ -256,30 +308,89 @@ impl Scene for LevelScene {
                         }
                     }
                 }
-                KeyCode::Q => {
+                Action::Cancel => {
                     // Cancel
                 }
-                KeyCode::E => {
-                    // Confirm
+                Action::Confirm => {
+                    // Confirm, or walk toward the clicked tile
+                    if let Some(t) = &data.game.turn_system.current_turn {
+                        if let TurnKind::Character { character_id } = &t.kind {
+                            let character_id = *character_id;
+                            let from = data.game.characters.get(&character_id).map(|c| c.position);
+                            let to = input.pointer.and_then(|p| data.game.position_at(p.x, p.y));
+                            match (from, to) {
+                                (Some(from), Some(to)) if from != to => {
+                                    if let Some((x, y)) = data.game.path_step(&from, &to) {
+                                        if let Ok(command) = Character::move_command(
+                                            &mut data.game,
+                                            character_id,
+                                            x,
+                                            y,
+                                        ) {
+                                            data.game.add_player_command(character_id, command);
+                                        }
+                                    }
+                                }
+                                _ => {
+                                    return Transition::Push(Box::new(ActionScene::new(
+                                        ctx,
+                                        &data,
+                                        character_id,
+                                    )));
+                                }
+                            }
+                        }
+                    }
+                }
+                Action::Journal => {
+                    return Transition::Push(Box::new(JournalScene::new(ctx, data)));
+                }
+                Action::Inventory => {
+                    // Items are used on the turn of their owner
                     if let Some(t) = &data.game.turn_system.current_turn {
                         if let TurnKind::Character { character_id } = &t.kind {
-                            return Transition::Push(Box::new(ActionScene::new(
+                            return Transition::Push(Box::new(InventoryScene::new(
                                 ctx,
-                                &data,
+                                data,
                                 *character_id,
                             )));
                         }
                     }
                 }
-                KeyCode::Escape => {
+                Action::CharacterSheet => {
+                    let character_id = data.game.camera.character_id;
+                    return Transition::Push(Box::new(CharacterSheetScene::new(
+                        ctx,
+                        data,
+                        character_id,
+                    )));
+                }
+                Action::Map => {
+                    return Transition::Push(Box::new(MapScene::new(ctx, data)));
+                }
+                Action::Menu => {
                     // Player can open pause menu anytime
                     return Transition::Push(Box::new(PauseScene::new(ctx, &data)));
                 }
                 _ => {}
             }
         } else {
-            match keycode {
-                KeyCode::Escape => {
+            match input.action {
+                Action::Journal => {
+                    return Transition::Push(Box::new(JournalScene::new(ctx, data)));
+                }
+                Action::CharacterSheet => {
+                    let character_id = data.game.camera.character_id;
+                    return Transition::Push(Box::new(CharacterSheetScene::new(
+                        ctx,
+                        data,
+                        character_id,
+                    )));
+                }
+                Action::Map => {
+                    return Transition::Push(Box::new(MapScene::new(ctx, data)));
+                }
+                Action::Menu => {
                     // Player can open pause menu anytime
                     return Transition::Push(Box::new(PauseScene::new(ctx, &data)));
                 }
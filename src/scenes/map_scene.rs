@@ -0,0 +1,64 @@
+use ggez::graphics;
+use ggez::mint::Point2;
+use ggez::Context;
+use ggez::GameResult;
+
+use crate::input::{Action, Input};
+use crate::scene::{Data, Scene, Transition};
+use crate::ui::minimap::Minimap;
+
+/// Explored tiles of the whole map, over the level
+pub struct MapScene {}
+
+impl MapScene {
+    pub fn new(_ctx: &mut Context, _data: &Data) -> Self {
+        Self {}
+    }
+}
+
+impl Scene for MapScene {
+    fn update(&mut self, _ctx: &mut Context, _data: &mut Data) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, data: &mut Data) -> GameResult<()> {
+        let bg = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new_i32(0, 0, 1280, 720),
+            graphics::Color::from_rgba(0, 0, 0, 223),
+        )?;
+        graphics::draw(ctx, &bg, graphics::DrawParam::new())?;
+
+        let localization = &data.game.localization;
+        let title = graphics::Text::new(localization.get("ui.map.title"));
+        graphics::draw(
+            ctx,
+            &title,
+            graphics::DrawParam::new().dest(Point2 { x: 32.0, y: 24.0 }),
+        )?;
+        let help = graphics::Text::new(localization.get("ui.map.help"));
+        graphics::draw(
+            ctx,
+            &help,
+            graphics::DrawParam::new().dest(Point2 { x: 32.0, y: 680.0 }),
+        )?;
+
+        Minimap::draw(
+            ctx,
+            &data.game,
+            graphics::Rect::new(32.0, 64.0, 1216.0, 600.0),
+            48.0,
+        )
+    }
+
+    fn input(&mut self, _ctx: &mut Context, input: Input, _data: &mut Data) -> Transition {
+        match input.action {
+            Action::Cancel | Action::Map | Action::Menu => {
+                // Cancel
+                Transition::Pop
+            }
+            _ => Transition::None,
+        }
+    }
+}
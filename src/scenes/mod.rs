@@ -1,7 +1,16 @@
 pub mod action_scene;
+pub mod backlog_scene;
+pub mod character_sheet_scene;
+pub mod creation_scene;
+pub mod inventory_scene;
+pub mod journal_scene;
 pub mod level_scene;
+pub mod map_scene;
 pub mod novel_scene;
 pub mod pause_scene;
 pub mod select_ability_scene;
+pub mod settings_scene;
 pub mod shop_scene;
+pub mod slot_scene;
 pub mod target_scene;
+pub mod title_scene;
@@ -1,25 +1,48 @@
 use super::{
-    pause_scene::PauseScene, select_ability_scene::SelectAbilityScene, shop_scene::ShopScene,
+    backlog_scene::BacklogScene, pause_scene::PauseScene, select_ability_scene::SelectAbilityScene,
+    shop_scene::ShopScene,
 };
+use crate::input::{Action, Input};
 use crate::scene::{Data, Scene, Transition};
-use ggez::event::KeyCode;
+use crate::ui::text_box::TextBox;
 use ggez::graphics;
+use ggez::timer;
 use ggez::Context;
 use ggez::GameResult;
-use ggez::{event::KeyMods, timer};
+use tower::core::NovelMode;
+
+const TEXT_BOX_WIDTH: f32 = 1248.0;
+const TEXT_BOX_LINES: usize = 4;
+const CHOICE_WIDTH: i32 = 512;
+const CHOICE_HEIGHT: i32 = 48;
+/// Choices shown at once, the list scrolls for more
+const VISIBLE_CHOICES: usize = 6;
 
 pub struct NovelScene {
-    pub text: Option<graphics::Text>,
+    text_box: TextBox,
+    /// Index in the history of the line in the text box
+    shown: Option<usize>,
     current_item: usize,
+    /// First choice shown
+    scroll: usize,
 }
 
 impl NovelScene {
-    pub fn new(ctx: &mut Context, data: &mut Data) -> Self {
-        // Load/create resources such as images here.
-        let text = None;
+    pub fn new(_ctx: &mut Context, _data: &mut Data) -> Self {
         Self {
-            text,
+            text_box: TextBox::new(TEXT_BOX_WIDTH, TEXT_BOX_LINES),
+            shown: None,
             current_item: 0,
+            scroll: 0,
+        }
+    }
+
+    /// Keep the current choice in the visible part of the list
+    fn scroll_to_current(&mut self) {
+        if self.current_item < self.scroll {
+            self.scroll = self.current_item;
+        } else if self.current_item >= self.scroll + VISIBLE_CHOICES {
+            self.scroll = self.current_item + 1 - VISIBLE_CHOICES;
         }
     }
 }
@@ -41,7 +64,35 @@ impl Scene for NovelScene {
                 ))));
             }
         }
-        data.game.update(timer::delta(&ctx).as_secs_f32());
+        let delta = timer::delta(&ctx).as_secs_f32();
+        let mode = data.game.novel_system.mode;
+        let auto_delay = data.game.novel_system.auto_delay;
+        if let Some(current) = &mut data.game.novel_system.current {
+            // New line
+            let line = current.line();
+            if line.map(|(i, _)| i) != self.shown {
+                self.shown = line.map(|(i, _)| i);
+                let text = line.map(|(_, l)| &l.text[..]).unwrap_or("");
+                self.text_box.set_text(ctx, text);
+            }
+            self.text_box.update(delta);
+            match mode {
+                NovelMode::Normal => {}
+                NovelMode::Skip => self.text_box.reveal(),
+                NovelMode::Auto => {
+                    // The delay counts once the page is revealed
+                    if !self.text_box.is_revealed() {
+                        current.elapsed = 0.0;
+                    } else if !self.text_box.is_done() && current.elapsed >= auto_delay {
+                        self.text_box.advance();
+                        current.elapsed = 0.0;
+                    }
+                }
+            }
+            // The line ends after its last page
+            current.page_done = self.text_box.is_done();
+        }
+        data.game.update(delta);
 
         Ok(Transition::None)
     }
@@ -71,41 +122,60 @@ impl Scene for NovelScene {
                 }
             }
             // Text
-            let bg = graphics::Mesh::new_rectangle(
-                ctx,
-                graphics::DrawMode::fill(),
-                graphics::Rect::new_i32(0, 0, 256, 64),
-                // graphics::BLACK,
-                graphics::Color::from_rgba(0, 0, 127, 127),
-            )?;
-            if let Some(s) = current.history.last() {
-                let x = 0.0;
-                let y = 0.0;
-                graphics::draw(
-                    ctx,
-                    &bg,
-                    graphics::DrawParam {
-                        dest: ggez::mint::Point2 { x, y },
-                        ..Default::default()
-                    },
-                )?;
-                // draw text
-                let text = graphics::Text::new(String::from(s));
-                graphics::draw(
-                    ctx,
-                    &text,
-                    graphics::DrawParam {
-                        dest: ggez::mint::Point2 { x, y },
-                        ..Default::default()
-                    },
-                )?;
+            let y = 720.0 - self.text_box.height();
+            if let Some((_, line)) = current.line() {
+                let mut x = 0.0;
+                // Portrait
+                if let Some(portrait) = &line.portrait {
+                    let image = data.image_caches.get(ctx, portrait)?;
+                    x = image.width() as f32;
+                    graphics::draw(
+                        ctx,
+                        image,
+                        graphics::DrawParam {
+                            dest: ggez::mint::Point2 {
+                                x: 0.0,
+                                y: y - image.height() as f32,
+                            },
+                            ..Default::default()
+                        },
+                    )?;
+                }
+                // Speaker
+                if let Some(speaker) = &line.speaker {
+                    let text = graphics::Text::new(
+                        graphics::TextFragment::new(&speaker[..])
+                            .color(graphics::Color::from_rgb(255, 255, 0)),
+                    );
+                    let height = text.height(ctx) as f32;
+                    graphics::draw(
+                        ctx,
+                        &text,
+                        graphics::DrawParam {
+                            dest: ggez::mint::Point2 {
+                                x: x + 8.0,
+                                y: y - height - 4.0,
+                            },
+                            ..Default::default()
+                        },
+                    )?;
+                }
+                self.text_box.draw(ctx, ggez::mint::Point2 { x: 0.0, y })?;
             }
 
             // Select
             if let Some(select) = &current.select {
-                for (i, s) in select.iter().enumerate() {
-                    let x = 0.0;
-                    let y = ((i + 1) * 64) as f32;
+                let bg = graphics::Mesh::new_rectangle(
+                    ctx,
+                    graphics::DrawMode::fill(),
+                    graphics::Rect::new_i32(0, 0, CHOICE_WIDTH, CHOICE_HEIGHT),
+                    graphics::Color::from_rgba(0, 0, 127, 191),
+                )?;
+                let x = ((1280 - CHOICE_WIDTH) / 2) as f32;
+                let top = 64.0;
+                let end = select.len().min(self.scroll + VISIBLE_CHOICES);
+                for (i, choice) in select.iter().enumerate().take(end).skip(self.scroll) {
+                    let y = top + ((i - self.scroll) as i32 * CHOICE_HEIGHT) as f32;
                     graphics::draw(
                         ctx,
                         &bg,
@@ -114,62 +184,102 @@ impl Scene for NovelScene {
                             ..Default::default()
                         },
                     )?;
-                    let text = graphics::Text::new(String::from(s));
+                    let text = match &choice.disabled {
+                        Some(reason) => graphics::Text::new(
+                            graphics::TextFragment::new(format!("{}\n{}", choice.text, reason))
+                                .color(graphics::Color::from_rgb(127, 127, 127)),
+                        ),
+                        None => graphics::Text::new(String::from(&choice.text[..])),
+                    };
                     graphics::draw(
                         ctx,
                         &text,
                         graphics::DrawParam {
-                            dest: ggez::mint::Point2 { x, y },
+                            dest: ggez::mint::Point2 { x: x + 8.0, y },
                             ..Default::default()
                         },
                     )?;
+                    if i == self.current_item {
+                        // Draw selection
+                        let highlight = graphics::Mesh::new_rectangle(
+                            ctx,
+                            graphics::DrawMode::stroke(4.0),
+                            graphics::Rect::new_i32(0, 0, CHOICE_WIDTH, CHOICE_HEIGHT),
+                            graphics::Color::from_rgb(255, 255, 0),
+                        )?;
+                        graphics::draw(
+                            ctx,
+                            &highlight,
+                            graphics::DrawParam {
+                                dest: ggez::mint::Point2 { x, y },
+                                ..Default::default()
+                            },
+                        )?;
+                    }
                 }
-
-                // Draw selection
-                let highlight = graphics::Mesh::new_rectangle(
-                    ctx,
-                    graphics::DrawMode::stroke(4.0),
-                    graphics::Rect::new_i32(0, 0, 256, 64),
-                    // graphics::BLACK,
-                    graphics::Color::from_rgb(255, 255, 0),
-                )?;
-                graphics::draw(
-                    ctx,
-                    &highlight,
-                    graphics::DrawParam {
-                        dest: ggez::mint::Point2 {
-                            x: 0.0,
-                            y: ((self.current_item + 1) * 64) as f32,
+                // More choices above or below
+                let arrow_x = x + CHOICE_WIDTH as f32 + 8.0;
+                if self.scroll > 0 {
+                    let text = graphics::Text::new("▲");
+                    graphics::draw(
+                        ctx,
+                        &text,
+                        graphics::DrawParam {
+                            dest: ggez::mint::Point2 { x: arrow_x, y: top },
+                            ..Default::default()
                         },
-                        ..Default::default()
-                    },
-                )?;
+                    )?;
+                }
+                if end < select.len() {
+                    let text = graphics::Text::new("▼");
+                    let y = top + (VISIBLE_CHOICES as i32 * CHOICE_HEIGHT - 24) as f32;
+                    graphics::draw(
+                        ctx,
+                        &text,
+                        graphics::DrawParam {
+                            dest: ggez::mint::Point2 { x: arrow_x, y },
+                            ..Default::default()
+                        },
+                    )?;
+                }
             } else {
                 self.current_item = 0;
+                self.scroll = 0;
             }
         }
+        // Mode
+        let mode = match data.game.novel_system.mode {
+            NovelMode::Normal => None,
+            NovelMode::Skip => Some("ui.novel.skip"),
+            NovelMode::Auto => Some("ui.novel.auto"),
+        };
+        if let Some(mode) = mode {
+            let text = graphics::Text::new(data.game.localization.get(mode));
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam {
+                    dest: ggez::mint::Point2 { x: 1200.0, y: 8.0 },
+                    ..Default::default()
+                },
+            )?;
+        }
         Ok(())
     }
 
-    fn key_down_event(
-        &mut self,
-        ctx: &mut Context,
-        keycode: KeyCode,
-        _keymods: KeyMods,
-        _repeat: bool,
-        data: &mut Data,
-    ) -> Transition {
-        match keycode {
-            KeyCode::W => {
+    fn input(&mut self, ctx: &mut Context, input: Input, data: &mut Data) -> Transition {
+        match input.action {
+            Action::Up => {
                 // Up
                 if self.current_item > 0 {
                     self.current_item -= 1;
+                    self.scroll_to_current();
                 }
             }
-            KeyCode::A => {
+            Action::Left => {
                 // Left
             }
-            KeyCode::S => {
+            Action::Down => {
                 // Down
                 let m = data
                     .game
@@ -184,14 +294,15 @@ impl Scene for NovelScene {
                 } else {
                     self.current_item = 0;
                 }
+                self.scroll_to_current();
             }
-            KeyCode::D => {
+            Action::Right => {
                 // Right
             }
-            KeyCode::Q => {
+            Action::Cancel => {
                 // Cancel
             }
-            KeyCode::E => {
+            Action::Confirm => {
                 // Confirm
                 if let Some(select) = data
                     .game
@@ -200,13 +311,25 @@ impl Scene for NovelScene {
                     .as_ref()
                     .and_then(|c| c.select.as_ref())
                 {
-                    // In selection
+                    // In selection, a click picks the clicked choice
+                    if let Some(p) = input.pointer {
+                        let left = ((1280 - CHOICE_WIDTH) / 2) as f32;
+                        let row = ((p.y - 64.0) / CHOICE_HEIGHT as f32).floor();
+                        let hit = p.x >= left
+                            && p.x < left + CHOICE_WIDTH as f32
+                            && row >= 0.0
+                            && (row as usize) < VISIBLE_CHOICES;
+                        if !hit || self.scroll + row as usize >= select.len() {
+                            return Transition::None;
+                        }
+                        self.current_item = self.scroll + row as usize;
+                    }
                     // Check index
                     if self.current_item < select.len() {
                         // do select
                         data.game.select_option(self.current_item);
                     }
-                } else {
+                } else if !self.text_box.advance() {
                     // Next
                     data.game.novel_run();
                 }
@@ -215,9 +338,28 @@ impl Scene for NovelScene {
                     return Transition::Pop;
                 }
             }
-            KeyCode::Escape => {
+            Action::Backlog => {
+                return Transition::Push(Box::new(BacklogScene::new(ctx, data)));
+            }
+            Action::Skip => {
+                // Skip read lines
+                let novel_system = &mut data.game.novel_system;
+                novel_system.mode = match novel_system.mode {
+                    NovelMode::Skip => NovelMode::Normal,
+                    _ => NovelMode::Skip,
+                };
+            }
+            Action::Auto => {
+                // Auto
+                let novel_system = &mut data.game.novel_system;
+                novel_system.mode = match novel_system.mode {
+                    NovelMode::Auto => NovelMode::Normal,
+                    _ => NovelMode::Auto,
+                };
+            }
+            Action::Menu => {
                 // Player can open pause menu anytime
-                return Transition::Push(Box::new(PauseScene::new(ctx, &data)));
+                return Transition::Push(Box::new(PauseScene::new(ctx, data)));
             }
             _ => {}
         }
@@ -1,17 +1,40 @@
+use ggez::graphics;
+use ggez::mint::Point2;
 use ggez::Context;
 use ggez::GameResult;
 
-use ggez::event::KeyCode;
-use ggez::event::KeyMods;
-use ggez::graphics;
-
+use super::{settings_scene::SettingsScene, slot_scene::SlotScene, title_scene::TitleScene};
+use crate::input::{Action, Input};
 use crate::scene::{Data, Scene, Transition};
+use crate::ui::menu::Menu;
 
-pub struct PauseScene {}
+const RESUME: usize = 0;
+const SAVE: usize = 1;
+const SETTINGS: usize = 2;
+const TITLE: usize = 3;
+
+pub struct PauseScene {
+    menu: Menu,
+}
 
 impl PauseScene {
-    pub fn new(_ctx: &mut Context, _data: &Data) -> Self {
-        Self {}
+    pub fn new(_ctx: &mut Context, data: &Data) -> Self {
+        Self {
+            menu: Menu::new(PauseScene::items(data), Point2 { x: 440.0, y: 240.0 }),
+        }
+    }
+
+    fn items(data: &Data) -> Vec<(String, bool)> {
+        let localization = &data.game.localization;
+        [
+            "ui.pause.resume",
+            "ui.pause.save",
+            "ui.pause.settings",
+            "ui.pause.title",
+        ]
+        .iter()
+        .map(|key| (String::from(localization.get(key)), true))
+        .collect()
     }
 }
 
@@ -28,50 +51,52 @@ impl Scene for PauseScene {
             graphics::Color::from_rgba(0, 0, 0, 127),
         )?;
         graphics::draw(ctx, &bg, graphics::DrawParam::new())?;
-
-        let text = graphics::Text::new("Press N to create new character");
-        graphics::draw(ctx, &text, graphics::DrawParam::default())?;
-        Ok(())
+        self.menu.draw(ctx)
     }
 
-    fn key_down_event(
-        &mut self,
-        _ctx: &mut Context,
-        keycode: KeyCode,
-        _keymods: KeyMods,
-        _repeat: bool,
-        data: &mut Data,
-    ) -> Transition {
-        match keycode {
-            KeyCode::W => {
+    fn input(&mut self, ctx: &mut Context, input: Input, data: &mut Data) -> Transition {
+        match input.action {
+            Action::Up => {
                 // Up
+                self.menu.up();
             }
-            KeyCode::A => {
-                // Left
-            }
-            KeyCode::S => {
+            Action::Down => {
                 // Down
+                self.menu.down();
             }
-            KeyCode::D => {
-                // Right
-            }
-            KeyCode::Q => {
+            Action::Cancel | Action::Menu => {
                 // Cancel
                 return Transition::Pop;
             }
-            KeyCode::E => {
+            Action::Confirm => {
                 // Confirm
-            }
-            KeyCode::N => {
-                data.game.new_player_character();
-                return Transition::Pop;
-            }
-            KeyCode::Escape => {
-                return Transition::Pop;
+                if let Some(p) = input.pointer {
+                    if !self.menu.point(p) {
+                        return Transition::None;
+                    }
+                }
+                match self.menu.selected() {
+                    Some(RESUME) => return Transition::Pop,
+                    Some(SAVE) => {
+                        return Transition::Push(Box::new(SlotScene::new(ctx, data, true)));
+                    }
+                    Some(SETTINGS) => {
+                        return Transition::Push(Box::new(SettingsScene::new(ctx, data)));
+                    }
+                    Some(TITLE) => {
+                        return Transition::Replace(Box::new(TitleScene::new(ctx, data)));
+                    }
+                    _ => {}
+                }
             }
             _ => {}
         }
 
         Transition::None
     }
+
+    fn on_resume(&mut self, _ctx: &mut Context, data: &mut Data) {
+        // The language may have changed
+        self.menu.items = PauseScene::items(data);
+    }
 }
@@ -1,10 +1,10 @@
 use super::pause_scene::PauseScene;
+use crate::input::{Action, Input};
 use crate::scene::{Data, Scene, Transition};
-use ggez::event::KeyCode;
 use ggez::graphics;
 use ggez::Context;
 use ggez::GameResult;
-use ggez::{event::KeyMods, nalgebra, timer};
+use ggez::{nalgebra, timer};
 
 pub struct SelectAbilityScene {
     character_id: i32,
@@ -25,7 +25,7 @@ impl SelectAbilityScene {
                 {
                     if let Some(a) = data.game.actions.get(&action_id) {
                         // draw buttom
-                        let text = graphics::Text::new(&a.name[..]);
+                        let text = graphics::Text::new(data.game.localization.get(&a.label));
                         action_texts.push(text);
                         target_kinds.push(a.target_kind);
                         action_ids.push(action_id);
@@ -104,49 +104,42 @@ impl Scene for SelectAbilityScene {
         Ok(())
     }
 
-    fn key_down_event(
-        &mut self,
-        ctx: &mut Context,
-        keycode: KeyCode,
-        _keymods: KeyMods,
-        _repeat: bool,
-        data: &mut Data,
-    ) -> Transition {
-        match keycode {
-            KeyCode::W => {
+    fn input(&mut self, ctx: &mut Context, input: Input, data: &mut Data) -> Transition {
+        match input.action {
+            Action::Up => {
                 // Up
                 if self.current_item >= 1 {
                     self.current_item -= 1;
                 }
             }
-            KeyCode::A => {
+            Action::Left => {
                 // Left
                 if self.current_item >= 5 {
                     self.current_item -= 5;
                 }
             }
-            KeyCode::S => {
+            Action::Down => {
                 // Down
                 if self.current_item + 1 < self.action_texts.len() {
                     self.current_item += 1;
                 }
             }
-            KeyCode::D => {
+            Action::Right => {
                 // Right
                 if self.current_item + 5 < self.action_texts.len() {
                     self.current_item += 5;
                 }
             }
-            KeyCode::Q => {
+            Action::Cancel => {
                 // Cancel
                 data.game.cancel_select_ability();
                 // return Transition::Pop;
             }
-            KeyCode::E => {
+            Action::Confirm => {
                 // Confirm
                 data.game.select_ability(self.current_item);
             }
-            KeyCode::Escape => {
+            Action::Menu => {
                 // Player can open pause menu anytime
                 return Transition::Push(Box::new(PauseScene::new(ctx, &data)));
             }
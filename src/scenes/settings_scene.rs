@@ -0,0 +1,128 @@
+use ggez::graphics;
+use ggez::mint::Point2;
+use ggez::Context;
+use ggez::GameResult;
+
+use crate::input::{Action, Input};
+use crate::scene::{Data, Scene, Transition};
+use crate::storage::{self, Settings};
+use crate::ui::menu::Menu;
+
+const LANGUAGE: usize = 0;
+const BACK: usize = 1;
+
+/// Player preferences, written to the user data directory when leaving
+pub struct SettingsScene {
+    menu: Menu,
+}
+
+impl SettingsScene {
+    pub fn new(_ctx: &mut Context, data: &Data) -> Self {
+        Self {
+            menu: Menu::new(SettingsScene::items(data), Point2 { x: 440.0, y: 240.0 }),
+        }
+    }
+
+    fn items(data: &Data) -> Vec<(String, bool)> {
+        let localization = &data.game.localization;
+        let language = format!("language.{}", localization.language);
+        vec![
+            (
+                localization.format(
+                    "ui.settings.language",
+                    &[("language", String::from(localization.get(&language)))],
+                ),
+                true,
+            ),
+            (String::from(localization.get("ui.settings.back")), true),
+        ]
+    }
+
+    /// Switch to the next or previous language
+    fn cycle_language(&mut self, data: &mut Data, forward: bool) {
+        let localization = &mut data.game.localization;
+        let languages = localization.languages();
+        let len = languages.len();
+        let next = languages
+            .iter()
+            .position(|l| **l == localization.language)
+            .map(|i| (if forward { i + 1 } else { i + len - 1 }) % len)
+            .and_then(|i| languages.get(i))
+            .map(|l| String::from(&l[..]));
+        if let Some(next) = next {
+            localization.set_language(&next);
+        }
+        self.menu.items = SettingsScene::items(data);
+    }
+}
+
+impl Scene for SettingsScene {
+    fn update(&mut self, _ctx: &mut Context, _data: &mut Data) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, data: &mut Data) -> GameResult<()> {
+        let bg = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new_i32(0, 0, 1280, 720),
+            graphics::Color::from_rgba(0, 0, 0, 223),
+        )?;
+        graphics::draw(ctx, &bg, graphics::DrawParam::new())?;
+
+        let title = graphics::Text::new(data.game.localization.get("ui.settings.title"));
+        graphics::draw(
+            ctx,
+            &title,
+            graphics::DrawParam::new().dest(Point2 { x: 456.0, y: 192.0 }),
+        )?;
+        self.menu.draw(ctx)
+    }
+
+    fn input(&mut self, _ctx: &mut Context, input: Input, data: &mut Data) -> Transition {
+        match input.action {
+            Action::Up => {
+                // Up
+                self.menu.up();
+            }
+            Action::Down => {
+                // Down
+                self.menu.down();
+            }
+            Action::Left if self.menu.current == LANGUAGE => {
+                // Previous language
+                self.cycle_language(data, false);
+            }
+            Action::Right if self.menu.current == LANGUAGE => {
+                // Next language
+                self.cycle_language(data, true);
+            }
+            Action::Cancel | Action::Menu => {
+                // Cancel
+                return Transition::Pop;
+            }
+            Action::Confirm => {
+                // Confirm
+                if let Some(p) = input.pointer {
+                    if !self.menu.point(p) {
+                        return Transition::None;
+                    }
+                }
+                match self.menu.selected() {
+                    Some(LANGUAGE) => self.cycle_language(data, true),
+                    Some(BACK) => return Transition::Pop,
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+
+        Transition::None
+    }
+
+    fn on_exit(&mut self, ctx: &mut Context, data: &mut Data) {
+        if let Err(e) = storage::save_settings(ctx, &Settings::from_game(&data.game)) {
+            log::error!("Cannot save settings: {}", e);
+        }
+    }
+}
@@ -1,10 +1,10 @@
 use super::pause_scene::PauseScene;
+use crate::input::{Action, Input};
 use crate::scene::{Data, Scene, Transition};
-use ggez::event::KeyCode;
 use ggez::graphics;
+use ggez::timer;
 use ggez::Context;
 use ggez::GameResult;
-use ggez::{event::KeyMods, timer};
 
 pub struct ShopScene {
     current_item: usize,
@@ -56,7 +56,7 @@ impl Scene for ShopScene {
                         },
                     )?;
                     let name = data.game.items.get(&s.item_id).map(|i| &i.name).unwrap();
-                    let text = graphics::Text::new(String::from(name));
+                    let text = graphics::Text::new(data.game.localization.get(name));
                     graphics::draw(
                         ctx,
                         &text,
@@ -121,9 +121,13 @@ impl Scene for ShopScene {
                     .map(|i| &i.description)
                     .unwrap();
 
-                let text = graphics::Text::new(format!(
-                    "You have: {} \nDescription: {}",
-                    quantity, description
+                let localization = &data.game.localization;
+                let text = graphics::Text::new(localization.format(
+                    "ui.shop.details",
+                    &[
+                        ("quantity", quantity.to_string()),
+                        ("description", String::from(localization.get(description))),
+                    ],
                 ));
                 graphics::draw(
                     ctx,
@@ -138,25 +142,18 @@ impl Scene for ShopScene {
         Ok(())
     }
 
-    fn key_down_event(
-        &mut self,
-        ctx: &mut Context,
-        keycode: KeyCode,
-        _keymods: KeyMods,
-        _repeat: bool,
-        data: &mut Data,
-    ) -> Transition {
-        match keycode {
-            KeyCode::W => {
+    fn input(&mut self, ctx: &mut Context, input: Input, data: &mut Data) -> Transition {
+        match input.action {
+            Action::Up => {
                 // Up
                 if self.current_item > 0 {
                     self.current_item -= 1;
                 }
             }
-            KeyCode::A => {
+            Action::Left => {
                 // Left
             }
-            KeyCode::S => {
+            Action::Down => {
                 // Down
                 let m = data
                     .game
@@ -173,21 +170,21 @@ impl Scene for ShopScene {
                     self.current_item = 0;
                 }
             }
-            KeyCode::D => {
+            Action::Right => {
                 // Right
             }
-            KeyCode::Q => {
+            Action::Cancel => {
                 // Cancel
                 data.game.close_shop();
                 if data.game.shop_system.current.is_none() {
                     return Transition::Pop;
                 }
             }
-            KeyCode::E => {
+            Action::Confirm => {
                 // Confirm
                 data.game.buy_item(self.current_item);
             }
-            KeyCode::Escape => {
+            Action::Menu => {
                 // Player can open pause menu anytime
                 return Transition::Push(Box::new(PauseScene::new(ctx, &data)));
             }
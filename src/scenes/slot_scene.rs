@@ -0,0 +1,123 @@
+use ggez::graphics;
+use ggez::mint::Point2;
+use ggez::Context;
+use ggez::GameResult;
+
+use crate::input::{Action, Input};
+use crate::scene::{Data, Scene, Transition};
+use crate::storage::{self, SLOTS};
+use crate::ui::menu::Menu;
+
+/// Save slots, saving writes the game in the chosen slot, loading pops with
+/// the `Save` of the chosen slot
+pub struct SlotScene {
+    saving: bool,
+    menu: Menu,
+}
+
+impl SlotScene {
+    pub fn new(ctx: &mut Context, data: &Data, saving: bool) -> Self {
+        let localization = &data.game.localization;
+        let items = storage::slots(ctx)
+            .into_iter()
+            .enumerate()
+            .map(|(slot, info)| {
+                let slot = (slot + 1).to_string();
+                match info {
+                    Some(info) => {
+                        let floor = info.floor.map(|f| f.to_string()).unwrap_or_default();
+                        let text = localization.format(
+                            "ui.slot.saved",
+                            &[
+                                ("slot", slot),
+                                ("floor", floor),
+                                ("saved_at", info.saved_at),
+                            ],
+                        );
+                        (text, true)
+                    }
+                    None => {
+                        let text = localization.format("ui.slot.empty", &[("slot", slot)]);
+                        (text, saving)
+                    }
+                }
+            })
+            .collect();
+        Self {
+            saving,
+            menu: Menu::new(items, Point2 { x: 440.0, y: 240.0 }),
+        }
+    }
+}
+
+impl Scene for SlotScene {
+    fn update(&mut self, _ctx: &mut Context, _data: &mut Data) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, data: &mut Data) -> GameResult<()> {
+        let bg = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new_i32(0, 0, 1280, 720),
+            graphics::Color::from_rgba(0, 0, 0, 223),
+        )?;
+        graphics::draw(ctx, &bg, graphics::DrawParam::new())?;
+
+        let key = if self.saving {
+            "ui.slot.save"
+        } else {
+            "ui.slot.load"
+        };
+        let title = graphics::Text::new(data.game.localization.get(key));
+        graphics::draw(
+            ctx,
+            &title,
+            graphics::DrawParam::new().dest(Point2 { x: 456.0, y: 192.0 }),
+        )?;
+        self.menu.draw(ctx)
+    }
+
+    fn input(&mut self, ctx: &mut Context, input: Input, data: &mut Data) -> Transition {
+        match input.action {
+            Action::Up => {
+                // Up
+                self.menu.up();
+            }
+            Action::Down => {
+                // Down
+                self.menu.down();
+            }
+            Action::Cancel | Action::Menu => {
+                // Cancel
+                return Transition::Pop;
+            }
+            Action::Confirm => {
+                // Confirm
+                if let Some(p) = input.pointer {
+                    if !self.menu.point(p) {
+                        return Transition::None;
+                    }
+                }
+                let slot = match self.menu.selected() {
+                    Some(slot) if slot < SLOTS => slot,
+                    _ => return Transition::None,
+                };
+                if self.saving {
+                    if let Err(e) = storage::save(ctx, slot, &data.game) {
+                        log::error!("Cannot save in slot {}: {}", slot, e);
+                        return Transition::Pop;
+                    }
+                    // Back to the game, past the pause menu
+                    return Transition::PopN(2);
+                }
+                if let Some(save) = storage::load(ctx, slot) {
+                    return Transition::pop_with(save);
+                }
+            }
+            _ => {}
+        }
+
+        Transition::None
+    }
+}
@@ -1,14 +1,13 @@
 use crate::{
+    input::{Action, Input},
     rendering::Rendering,
     scene::{Data, Scene, Transition},
-    ui::stats::Stats,
+    ui::{stats::Stats, timeline::Timeline},
 };
-use ggez::event::KeyCode;
-use ggez::event::KeyMods;
 use ggez::graphics;
 use ggez::Context;
 use ggez::GameResult;
-use tower::core::{Command, Position, Target, TargetKind};
+use tower::core::{Command, Game, Position, Target, TargetKind};
 
 pub struct TargetScene {
     character_id: i32,
@@ -32,6 +31,18 @@ impl TargetScene {
             position,
         }
     }
+
+    /// Target of the action at the cursor, None if there is nothing to target
+    fn target(&self, game: &Game) -> Option<Target> {
+        let target_kind = game.actions.get(&self.action_id).map(|a| a.target_kind)?;
+        match target_kind {
+            TargetKind::None => Some(Target::None),
+            TargetKind::Character => game
+                .character_at_position(&self.position)
+                .map(Target::Character),
+            TargetKind::Position => Some(Target::Position(self.position)),
+        }
+    }
 }
 
 impl Scene for TargetScene {
@@ -51,7 +62,7 @@ impl Scene for TargetScene {
         if let Some(id) = data.game.character_at_position(&self.position) {
             let character = data.game.characters.get(&id).unwrap();
 
-            let stats_ui = Stats::new(ctx, character)?;
+            let stats_ui = Stats::new(ctx, character, &data.game.localization)?;
             stats_ui.draw_canvas(ctx)?;
             graphics::draw(
                 ctx,
@@ -65,66 +76,63 @@ impl Scene for TargetScene {
         Ok(())
     }
 
-    fn key_down_event(
-        &mut self,
-        _ctx: &mut Context,
-        keycode: KeyCode,
-        _keymods: KeyMods,
-        _repeat: bool,
-        data: &mut Data,
-    ) -> Transition {
-        match keycode {
-            KeyCode::W => {
+    fn draw_top(&mut self, ctx: &mut Context, data: &mut Data) -> GameResult {
+        // Turn order if the action is used on the target at the cursor
+        let target = self.target(&data.game).unwrap_or(Target::None);
+        Timeline::draw_preview(
+            ctx,
+            &mut data.image_caches,
+            &data.game,
+            self.character_id,
+            self.action_id,
+            &target,
+            ggez::mint::Point2 { x: 720.0, y: 128.0 },
+        )
+    }
+
+    fn input(&mut self, _ctx: &mut Context, input: Input, data: &mut Data) -> Transition {
+        match input.action {
+            Action::Up => {
                 // Up
                 self.position.y -= 1;
             }
-            KeyCode::A => {
+            Action::Left => {
                 // Left
                 self.position.x -= 1;
             }
-            KeyCode::S => {
+            Action::Down => {
                 // Down
                 self.position.y += 1;
             }
-            KeyCode::D => {
+            Action::Right => {
                 // Right
                 self.position.x += 1;
             }
-            KeyCode::Q => {
+            Action::Cancel => {
                 // Cancel
-                data.target_scene.finish = false;
                 return Transition::Pop;
             }
-            KeyCode::E => {
-                // Confirm
-                let target_kind = data
-                    .game
-                    .actions
-                    .get(&self.action_id)
-                    .map(|a| a.target_kind)
-                    .unwrap();
-
-                let target = match target_kind {
-                    TargetKind::None => Some(Target::None),
-                    TargetKind::Character => {
-                        match data.game.character_at_position(&self.position) {
-                            Some(id) => Some(Target::Character(id)),
-                            None => None,
+            Action::Confirm => {
+                // Confirm, a click on another tile moves the cursor there
+                if let Some(p) = input.pointer {
+                    if let Some(position) = data.game.position_at(p.x, p.y) {
+                        if position != self.position {
+                            self.position = position;
+                            return Transition::None;
                         }
                     }
-                    TargetKind::Position => Some(Target::Position(self.position)),
-                };
+                }
+                let target = self.target(&data.game);
                 if let Some(target) = target {
                     if let Ok(command) =
                         Command::new(&mut data.game, self.character_id, self.action_id, target)
                     {
                         data.game.add_player_command(self.character_id, command);
-                        data.target_scene.finish = true;
-                        return Transition::Pop;
+                        return Transition::pop_with(target);
                     }
                 }
             }
-            KeyCode::Escape => {
+            Action::Menu => {
                 // Open menu
             }
             _ => {}
@@ -0,0 +1,150 @@
+use ggez::event;
+use ggez::graphics;
+use ggez::mint::Point2;
+use ggez::Context;
+use ggez::GameResult;
+use std::any::Any;
+
+use super::{
+    creation_scene::CreationScene, level_scene::LevelScene, settings_scene::SettingsScene,
+    slot_scene::SlotScene,
+};
+use crate::input::{Action, Input};
+use crate::scene::{Data, Scene, Transition};
+use crate::storage::{self, Save};
+use crate::ui::menu::Menu;
+
+const NEW_GAME: usize = 0;
+const CONTINUE: usize = 1;
+const LOAD: usize = 2;
+const SETTINGS: usize = 3;
+const QUIT: usize = 4;
+
+/// First scene, starts or loads a game
+pub struct TitleScene {
+    menu: Menu,
+    /// Slot saved last
+    latest: Option<usize>,
+}
+
+impl TitleScene {
+    pub fn new(ctx: &mut Context, data: &mut Data) -> Self {
+        let mut scene = Self {
+            menu: Menu::new(Vec::new(), Point2 { x: 440.0, y: 360.0 }),
+            latest: None,
+        };
+        scene.refresh(ctx, data);
+        scene
+    }
+
+    /// Rebuild the entries, for saves and language changes
+    fn refresh(&mut self, ctx: &mut Context, data: &Data) {
+        let slots = storage::slots(ctx);
+        self.latest = storage::latest(&slots);
+        let localization = &data.game.localization;
+        let items = vec![
+            (localization.get("ui.title.new_game"), true),
+            (localization.get("ui.title.continue"), self.latest.is_some()),
+            (localization.get("ui.title.load"), self.latest.is_some()),
+            (localization.get("ui.title.settings"), true),
+            (localization.get("ui.title.quit"), true),
+        ];
+        let current = self.menu.current;
+        self.menu = Menu::new(
+            items
+                .into_iter()
+                .map(|(s, e)| (String::from(s), e))
+                .collect(),
+            self.menu.position,
+        );
+        if self.menu.items.get(current).map(|(_, e)| *e) == Some(true) {
+            self.menu.current = current;
+        }
+    }
+
+    /// Play the save
+    fn start(ctx: &mut Context, data: &mut Data, save: Save) -> Transition {
+        storage::replace_game(&mut data.game, save.game);
+        Transition::Replace(Box::new(LevelScene::new(ctx, data)))
+    }
+}
+
+impl Scene for TitleScene {
+    fn update(&mut self, _ctx: &mut Context, _data: &mut Data) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, data: &mut Data) -> GameResult<()> {
+        graphics::clear(ctx, graphics::BLACK);
+        let mut title = graphics::Text::new(data.game.localization.get("ui.title.name"));
+        title.set_font(graphics::Font::default(), graphics::Scale::uniform(64.0));
+        let width = title.width(ctx) as f32;
+        graphics::draw(
+            ctx,
+            &title,
+            graphics::DrawParam::new().dest(Point2 {
+                x: (1280.0 - width) / 2.0,
+                y: 160.0,
+            }),
+        )?;
+        self.menu.draw(ctx)
+    }
+
+    fn input(&mut self, ctx: &mut Context, input: Input, data: &mut Data) -> Transition {
+        match input.action {
+            Action::Up => {
+                // Up
+                self.menu.up();
+            }
+            Action::Down => {
+                // Down
+                self.menu.down();
+            }
+            Action::Confirm => {
+                // Confirm
+                if let Some(p) = input.pointer {
+                    if !self.menu.point(p) {
+                        return Transition::None;
+                    }
+                }
+                match self.menu.selected() {
+                    Some(NEW_GAME) => {
+                        return Transition::Push(Box::new(CreationScene::new(ctx, data)));
+                    }
+                    Some(CONTINUE) => {
+                        if let Some(save) = self.latest.and_then(|slot| storage::load(ctx, slot)) {
+                            return TitleScene::start(ctx, data, save);
+                        }
+                    }
+                    Some(LOAD) => {
+                        return Transition::Push(Box::new(SlotScene::new(ctx, data, false)));
+                    }
+                    Some(SETTINGS) => {
+                        return Transition::Push(Box::new(SettingsScene::new(ctx, data)));
+                    }
+                    Some(QUIT) => event::quit(ctx),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+
+        Transition::None
+    }
+
+    fn on_resume(&mut self, ctx: &mut Context, data: &mut Data) {
+        self.refresh(ctx, data);
+    }
+
+    fn on_result(
+        &mut self,
+        ctx: &mut Context,
+        data: &mut Data,
+        result: Box<dyn Any>,
+    ) -> Transition {
+        match result.downcast::<Save>() {
+            Ok(save) => TitleScene::start(ctx, data, *save),
+            Err(_) => Transition::None,
+        }
+    }
+}
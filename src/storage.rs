@@ -0,0 +1,309 @@
+use ggez::{filesystem, Context, GameResult};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::io::Read;
+use tower::core::{Game, Localization, NovelSystem};
+
+/// Save slots, stored as `/saves/<slot>.ron` in the user data directory, with
+/// their `SaveInfo` alone in `/saves/<slot>.info.ron`
+pub const SLOTS: usize = 3;
+
+/// New game from the content in `/game`
+pub fn load_game(ctx: &mut Context) -> Game {
+    let characters = filesystem::open(ctx, "/game/characters.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let commands = filesystem::open(ctx, "/game/commands.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let character_actions = filesystem::open(ctx, "/game/character_actions.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let character_templates = filesystem::open(ctx, "/game/character_templates.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let actions = filesystem::open(ctx, "/game/actions.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let items = filesystem::open(ctx, "/game/items.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let tile_maps = filesystem::open(ctx, "/game/tile_maps.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let teleportations = filesystem::open(ctx, "/game/teleportations.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let tiles = filesystem::open(ctx, "/game/tiles.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let tile_sheets = filesystem::open(ctx, "/game/tile_sheets.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let character_sprites = filesystem::open(ctx, "/game/character_sprites.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let sprite_sheets = filesystem::open(ctx, "/game/sprite_sheets.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let sprite_sequences = filesystem::open(ctx, "/game/sprite_sequences.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let sprite_animations = filesystem::open(ctx, "/game/sprite_animations.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let areas = filesystem::open(ctx, "/game/areas.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let quests = filesystem::open(ctx, "/game/quests.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let player_classes = filesystem::open(ctx, "/game/player_classes.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let camera = filesystem::open(ctx, "/game/camera.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let turn_system = filesystem::open(ctx, "/game/turn_system.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let mut novel_system: NovelSystem = filesystem::open(ctx, "/game/novel_system.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    load_scripts(ctx, &mut novel_system);
+    let mut localization: Localization = filesystem::open(ctx, "/game/localization.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    load_languages(ctx, &mut localization);
+    let shop_system = filesystem::open(ctx, "/game/shop_system.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let floor_system = filesystem::open(ctx, "/game/floor_system.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let variable_system = filesystem::open(ctx, "/game/variable_system.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    let quest_system = filesystem::open(ctx, "/game/quest_system.ron")
+        .map(|f| ron::de::from_reader(f).unwrap())
+        .unwrap();
+    Game {
+        characters,
+        commands,
+        character_actions,
+        character_templates,
+        actions,
+        items,
+        tile_maps,
+        teleportations,
+        tiles,
+        tile_sheets,
+        character_sprites,
+        sprite_sheets,
+        sprite_sequences,
+        sprite_animations,
+        areas,
+        player_classes,
+        quests,
+        camera,
+        turn_system,
+        novel_system,
+        shop_system,
+        floor_system,
+        variable_system,
+        quest_system,
+        localization,
+        events: Default::default(),
+        exploration_system: Default::default(),
+    }
+}
+
+/// Load the string tables in `/game/lang`, named `<language>.ron`
+fn load_languages(ctx: &mut Context, localization: &mut Localization) {
+    let paths = match filesystem::read_dir(ctx, "/game/lang") {
+        Ok(paths) => paths.collect::<Vec<_>>(),
+        Err(_) => return,
+    };
+    for path in paths {
+        if path.extension().and_then(|e| e.to_str()) != Some("ron") {
+            continue;
+        }
+        let language = match path.file_stem().and_then(|s| s.to_str()) {
+            Some(language) => String::from(language),
+            None => continue,
+        };
+        match filesystem::open(ctx, &path).map(ron::de::from_reader) {
+            Ok(Ok(table)) => {
+                localization.tables.insert(language, table);
+            }
+            Ok(Err(e)) => log::error!("{:?}: {}", path, e),
+            Err(e) => log::error!("Cannot read {:?}: {}", path, e),
+        }
+    }
+}
+
+/// Compile the text scripts in `/game/scripts`, named `<id>.script`
+fn load_scripts(ctx: &mut Context, novel_system: &mut NovelSystem) {
+    let paths = match filesystem::read_dir(ctx, "/game/scripts") {
+        Ok(paths) => paths.collect::<Vec<_>>(),
+        Err(_) => return,
+    };
+    let errors = novel_system.load_scripts(paths, |path| {
+        let mut source = String::new();
+        filesystem::open(ctx, path)
+            .and_then(|mut f| f.read_to_string(&mut source).map_err(ggez::GameError::from))
+            .map(|_| source)
+            .map_err(|e| e.to_string())
+    });
+    for e in errors {
+        log::error!("{}", e);
+    }
+}
+
+/// Put `game` in place of `current`, keeping the string tables and language of
+/// `current`
+pub fn replace_game(current: &mut Game, game: Game) {
+    let previous = std::mem::replace(current, game);
+    current.localization = previous.localization;
+}
+
+/// Shown in the save slots
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SaveInfo {
+    /// Seconds since the epoch
+    pub timestamp: i64,
+    /// Local time of the save, for display
+    pub saved_at: String,
+    pub floor: Option<i32>,
+}
+
+#[derive(Serialize)]
+struct SaveRef<'a> {
+    info: SaveInfo,
+    game: &'a Game,
+}
+
+#[derive(Deserialize)]
+pub struct Save {
+    pub info: SaveInfo,
+    pub game: Game,
+}
+
+fn save_path(slot: usize) -> String {
+    format!("/saves/{}.ron", slot)
+}
+
+fn info_path(slot: usize) -> String {
+    format!("/saves/{}.info.ron", slot)
+}
+
+fn read<T: DeserializeOwned>(ctx: &mut Context, path: &str) -> Option<T> {
+    if !filesystem::exists(ctx, path) {
+        return None;
+    }
+    match filesystem::open(ctx, path).map(ron::de::from_reader) {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(e)) => {
+            log::error!("{}: {}", path, e);
+            None
+        }
+        Err(e) => {
+            log::error!("Cannot read {}: {}", path, e);
+            None
+        }
+    }
+}
+
+pub fn save(ctx: &mut Context, slot: usize, game: &Game) -> GameResult {
+    let now = chrono::Local::now();
+    let info = SaveInfo {
+        timestamp: now.timestamp(),
+        saved_at: now.format("%Y-%m-%d %H:%M").to_string(),
+        floor: game.floor_system.current.as_ref().map(|c| c.floor),
+    };
+    filesystem::create_dir(ctx, "/saves")?;
+    let f = filesystem::create(ctx, save_path(slot))?;
+    let pretty = ron::ser::PrettyConfig::new();
+    ron::ser::to_writer_pretty(
+        f,
+        &SaveRef {
+            info: info.clone(),
+            game,
+        },
+        pretty.clone(),
+    )
+    .map_err(|e| ggez::GameError::ResourceLoadError(e.to_string()))?;
+    let f = filesystem::create(ctx, info_path(slot))?;
+    ron::ser::to_writer_pretty(f, &info, pretty)
+        .map_err(|e| ggez::GameError::ResourceLoadError(e.to_string()))
+}
+
+pub fn load(ctx: &mut Context, slot: usize) -> Option<Save> {
+    read(ctx, &save_path(slot))
+}
+
+/// Info of every slot, None for the empty ones
+pub fn slots(ctx: &mut Context) -> Vec<Option<SaveInfo>> {
+    (0..SLOTS)
+        .map(|slot| {
+            if !filesystem::exists(ctx, save_path(slot)) {
+                return None;
+            }
+            // Saves made before the info file only have it inside
+            read(ctx, &info_path(slot)).or_else(|| load(ctx, slot).map(|save| save.info))
+        })
+        .collect()
+}
+
+/// Slot saved last, for Continue
+pub fn latest(slots: &[Option<SaveInfo>]) -> Option<usize> {
+    slots
+        .iter()
+        .enumerate()
+        .filter_map(|(slot, info)| info.as_ref().map(|info| (slot, info.timestamp)))
+        .max_by_key(|(_, timestamp)| *timestamp)
+        .map(|(slot, _)| slot)
+}
+
+/// Player preferences, stored as `/settings.ron` in the user data directory
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Settings {
+    pub language: String,
+}
+
+impl Settings {
+    pub fn from_game(game: &Game) -> Self {
+        Self {
+            language: game.localization.language.clone(),
+        }
+    }
+
+    pub fn apply(&self, game: &mut Game) {
+        if !game.localization.set_language(&self.language) {
+            log::warn!("No language {}", self.language);
+        }
+    }
+}
+
+pub fn load_settings(ctx: &mut Context) -> Option<Settings> {
+    if !filesystem::exists(ctx, "/settings.ron") {
+        return None;
+    }
+    match filesystem::open(ctx, "/settings.ron").map(ron::de::from_reader) {
+        Ok(Ok(settings)) => Some(settings),
+        Ok(Err(e)) => {
+            log::error!("/settings.ron: {}", e);
+            None
+        }
+        Err(e) => {
+            log::error!("Cannot read /settings.ron: {}", e);
+            None
+        }
+    }
+}
+
+pub fn save_settings(ctx: &mut Context, settings: &Settings) -> GameResult {
+    let f = filesystem::create(ctx, "/settings.ron")?;
+    let pretty = ron::ser::PrettyConfig::new();
+    ron::ser::to_writer_pretty(f, settings, pretty)
+        .map_err(|e| ggez::GameError::ResourceLoadError(e.to_string()))
+}
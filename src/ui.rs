@@ -1 +1,7 @@
+pub mod combat_effects;
+pub mod combat_log;
+pub mod menu;
+pub mod minimap;
 pub mod stats;
+pub mod text_box;
+pub mod timeline;
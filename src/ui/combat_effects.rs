@@ -0,0 +1,207 @@
+use crate::data::ImageCache;
+use crate::rendering::{Rendering, RenderingParam};
+use ggez::{graphics, Context, GameResult};
+use std::collections::HashMap;
+use tower::core::{CrowdControlKind, Game, GameEvent, Position};
+
+/// Seconds a floating text stays, rising one tile
+const TEXT_TIME: f32 = 1.0;
+/// Seconds a hit target flashes and shakes
+const HIT_TIME: f32 = 0.3;
+/// Seconds a defeated character takes to fade out
+const FADE_TIME: f32 = 0.8;
+
+struct FloatingText {
+    text: String,
+    color: (u8, u8, u8),
+    position: Position,
+    time: f32,
+}
+
+/// Sprite of a defeated character, drawn fading where it fell
+struct DeathFade {
+    avatar: String,
+    position: Position,
+    time: f32,
+}
+
+/// Floating combat text, hit flashes and death fades, spawned from the game
+/// events. Only drawn, the game does not wait for them.
+pub struct CombatEffects {
+    /// Events already turned into effects
+    seen: usize,
+    texts: Vec<FloatingText>,
+    /// Seconds left of the hit flash, by character id
+    hits: HashMap<i32, f32>,
+    fades: Vec<DeathFade>,
+}
+
+impl CombatEffects {
+    /// Effects of the events pushed from now on
+    pub fn new(game: &Game) -> Self {
+        Self {
+            seen: game.events.count,
+            texts: Vec::new(),
+            hits: HashMap::new(),
+            fades: Vec::new(),
+        }
+    }
+
+    pub fn update(&mut self, game: &Game, delta: f32) {
+        for text in self.texts.iter_mut() {
+            text.time += delta;
+        }
+        self.texts.retain(|t| t.time < TEXT_TIME);
+        for time in self.hits.values_mut() {
+            *time -= delta;
+        }
+        self.hits.retain(|_, time| *time > 0.0);
+        for fade in self.fades.iter_mut() {
+            fade.time += delta;
+        }
+        self.fades.retain(|f| f.time < FADE_TIME);
+
+        for event in game.events.since(self.seen) {
+            self.spawn(game, event);
+        }
+        self.seen = game.events.count;
+    }
+
+    fn spawn(&mut self, game: &Game, event: &GameEvent) {
+        let localization = &game.localization;
+        let position = |id: &i32| game.characters.get(id).map(|c| c.position);
+        let (text, color, position) = match event {
+            GameEvent::Damaged {
+                target_id, amount, ..
+            } => {
+                self.hits.insert(*target_id, HIT_TIME);
+                (format!("-{}", amount), (255, 63, 63), position(target_id))
+            }
+            GameEvent::Healed {
+                target_id, amount, ..
+            } => (format!("+{}", amount), (63, 255, 63), position(target_id)),
+            GameEvent::Missed { position, .. } => (
+                String::from(localization.get("ui.combat.miss")),
+                (255, 255, 255),
+                Some(*position),
+            ),
+            GameEvent::StatusApplied {
+                target_id, kind, ..
+            } => {
+                let key = match kind {
+                    CrowdControlKind::Stun => "ui.combat.stun",
+                    CrowdControlKind::Charm => "ui.combat.charm",
+                    CrowdControlKind::Shock => "ui.combat.shock",
+                    CrowdControlKind::Poison => "ui.combat.poison",
+                    CrowdControlKind::Sleep => "ui.combat.sleep",
+                    CrowdControlKind::Root => "ui.combat.root",
+                    CrowdControlKind::Silent => "ui.combat.silent",
+                };
+                (
+                    String::from(localization.get(key)),
+                    (255, 255, 0),
+                    position(target_id),
+                )
+            }
+            GameEvent::ExperienceDrained {
+                target_id, amount, ..
+            } => (
+                localization.format("ui.combat.drained", &[("amount", amount.to_string())]),
+                (191, 63, 255),
+                position(target_id),
+            ),
+            GameEvent::LevelUp { character_id, .. } => (
+                String::from(localization.get("ui.combat.level_up")),
+                (255, 255, 0),
+                position(character_id),
+            ),
+            GameEvent::Defeated { target_id, .. } => {
+                self.hits.remove(target_id);
+                if let Some(c) = game.characters.get(target_id) {
+                    if let Some(sprite) = game.character_sprites.get(&c.character_sprite_id) {
+                        self.fades.push(DeathFade {
+                            avatar: sprite.avatar.clone(),
+                            position: c.position,
+                            time: 0.0,
+                        });
+                    }
+                }
+                return;
+            }
+            GameEvent::ItemGained { .. } => return,
+        };
+        if let Some(position) = position {
+            self.texts.push(FloatingText {
+                text,
+                color,
+                position,
+                time: 0.0,
+            });
+        }
+    }
+
+    /// (x offset in tiles, flash alpha) of a character hit recently
+    pub fn hit(&self, character_id: i32) -> Option<(f32, u8)> {
+        self.hits.get(&character_id).map(|time| {
+            let progress = 1.0 - time / HIT_TIME;
+            let shake = (progress * std::f32::consts::PI * 6.0).sin() * 0.05;
+            (shake, (time / HIT_TIME * 191.0) as u8)
+        })
+    }
+
+    /// Death fades, drawn with the characters
+    pub fn draw_fades(
+        &self,
+        ctx: &mut Context,
+        image_caches: &mut ImageCache,
+        game: &Game,
+    ) -> GameResult<()> {
+        for fade in self.fades.iter() {
+            let alpha = 1.0 - fade.time / FADE_TIME;
+            let image = image_caches.get(ctx, &fade.avatar)?;
+            RenderingParam::default()
+                .position(&fade.position)
+                .target_size(64.0, 64.0)
+                .color(graphics::Color::new(1.0, 1.0, 1.0, alpha))
+                .draw_image(game, image, ctx)?;
+        }
+        Ok(())
+    }
+
+    /// Floating texts, drawn over the map
+    pub fn draw_texts(&self, ctx: &mut Context, game: &Game) -> GameResult<()> {
+        for t in self.texts.iter() {
+            let progress = t.time / TEXT_TIME;
+            let (r, g, b) = t.color;
+            let alpha = (255.0 * (1.0 - progress)) as u8;
+            let text = graphics::Text::new(
+                graphics::TextFragment::new(t.text.as_str())
+                    .color(graphics::Color::from_rgba(r, g, b, alpha)),
+            );
+            // Centered on the tile, rising
+            let tile_size = game.camera.tile_size as f32;
+            let x = t.position.x as f32 + 0.5 - text.width(ctx) as f32 / tile_size / 2.0;
+            let y = t.position.y as f32 - progress;
+            Rendering::draw(ctx, &text, t.position.map_id, x, y, game)?;
+        }
+        Ok(())
+    }
+
+    /// Flash over the sprite of a character hit recently
+    pub fn draw_hit(
+        ctx: &mut Context,
+        game: &Game,
+        map_id: i32,
+        x: f32,
+        y: f32,
+        alpha: u8,
+    ) -> GameResult<()> {
+        let flash = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new_i32(0, 0, 64, 64),
+            graphics::Color::from_rgba(255, 255, 255, alpha),
+        )?;
+        Rendering::draw(ctx, &flash, map_id, x, y, game)
+    }
+}
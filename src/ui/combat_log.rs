@@ -0,0 +1,166 @@
+use ggez::mint::Point2;
+use ggez::{graphics, Context, GameResult};
+use tower::core::{CrowdControlKind, Game, GameEvent};
+
+const VISIBLE_LINES: usize = 8;
+const LINE_HEIGHT: f32 = 24.0;
+const WIDTH: f32 = 544.0;
+
+/// Latest game events, scrollable toward older ones
+#[derive(Default)]
+pub struct CombatLog {
+    /// Lines scrolled up from the latest one
+    pub offset: usize,
+}
+
+impl CombatLog {
+    pub fn scroll_up(&mut self, game: &Game) {
+        let max = game.events.events.len().saturating_sub(VISIBLE_LINES);
+        self.offset = (self.offset + 1).min(max);
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.offset = self.offset.saturating_sub(1);
+    }
+
+    /// Text of the event, in the current language
+    pub fn line(game: &Game, event: &GameEvent) -> String {
+        let localization = &game.localization;
+        let name = |id: &i32| {
+            game.characters
+                .get(id)
+                .map(|c| String::from(localization.get(&c.name)))
+                .unwrap_or_else(|| String::from("?"))
+        };
+        match event {
+            GameEvent::Damaged {
+                target_id,
+                source_id: Some(source_id),
+                amount,
+            } => localization.format(
+                "ui.log.damaged_by",
+                &[
+                    ("source", name(source_id)),
+                    ("target", name(target_id)),
+                    ("amount", amount.to_string()),
+                ],
+            ),
+            GameEvent::Damaged {
+                target_id,
+                source_id: None,
+                amount,
+            } => localization.format(
+                "ui.log.damaged",
+                &[("target", name(target_id)), ("amount", amount.to_string())],
+            ),
+            GameEvent::Healed {
+                target_id, amount, ..
+            } => localization.format(
+                "ui.log.healed",
+                &[("target", name(target_id)), ("amount", amount.to_string())],
+            ),
+            GameEvent::Missed { source_id, .. } => {
+                localization.format("ui.log.missed", &[("source", name(source_id))])
+            }
+            GameEvent::StatusApplied {
+                target_id,
+                kind,
+                duration,
+                ..
+            } => {
+                let key = match kind {
+                    CrowdControlKind::Stun => "ui.log.stun",
+                    CrowdControlKind::Charm => "ui.log.charm",
+                    CrowdControlKind::Shock => "ui.log.shock",
+                    CrowdControlKind::Poison => "ui.log.poison",
+                    CrowdControlKind::Sleep => "ui.log.sleep",
+                    CrowdControlKind::Root => "ui.log.root",
+                    CrowdControlKind::Silent => "ui.log.silent",
+                };
+                localization.format(
+                    key,
+                    &[("target", name(target_id)), ("turns", duration.to_string())],
+                )
+            }
+            GameEvent::ExperienceDrained {
+                target_id,
+                source_id,
+                amount,
+            } => localization.format(
+                "ui.log.drained",
+                &[
+                    ("source", name(source_id)),
+                    ("target", name(target_id)),
+                    ("amount", amount.to_string()),
+                ],
+            ),
+            GameEvent::LevelUp {
+                character_id,
+                level,
+            } => localization.format(
+                "ui.log.level_up",
+                &[("name", name(character_id)), ("level", level.to_string())],
+            ),
+            GameEvent::Defeated {
+                target_id,
+                source_id: Some(source_id),
+            } => localization.format(
+                "ui.log.defeated_by",
+                &[("source", name(source_id)), ("target", name(target_id))],
+            ),
+            GameEvent::Defeated {
+                target_id,
+                source_id: None,
+            } => localization.format("ui.log.defeated", &[("target", name(target_id))]),
+            GameEvent::ItemGained {
+                character_id,
+                item_id,
+                quantity,
+            } => {
+                let item = game
+                    .items
+                    .get(item_id)
+                    .map(|item| localization.get(&item.name))
+                    .unwrap_or("?");
+                localization.format(
+                    "ui.log.item",
+                    &[
+                        ("name", name(character_id)),
+                        ("item", String::from(item)),
+                        ("quantity", quantity.to_string()),
+                    ],
+                )
+            }
+        }
+    }
+
+    /// Draw the visible lines, oldest at the top, from `dest`
+    pub fn draw(&self, ctx: &mut Context, game: &Game, dest: Point2<f32>) -> GameResult<()> {
+        let events = &game.events.events;
+        let end = events.len().saturating_sub(self.offset);
+        let start = end.saturating_sub(VISIBLE_LINES);
+        if start == end {
+            return Ok(());
+        }
+
+        let bg = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(0.0, 0.0, WIDTH, VISIBLE_LINES as f32 * LINE_HEIGHT + 16.0),
+            graphics::Color::from_rgba(0, 0, 0, 159),
+        )?;
+        graphics::draw(ctx, &bg, graphics::DrawParam::new().dest(dest))?;
+        for (i, event) in events.range(start..end).enumerate() {
+            let text = graphics::Text::new(CombatLog::line(game, event));
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam::new().dest(Point2 {
+                    x: dest.x + 8.0,
+                    y: dest.y + 8.0 + i as f32 * LINE_HEIGHT,
+                }),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use ggez::{graphics, mint::Point2, Context, GameResult};
+
+const ITEM_WIDTH: f32 = 400.0;
+const ITEM_HEIGHT: f32 = 48.0;
+
+/// Vertical list of entries, disabled ones are greyed and skipped
+pub struct Menu {
+    /// (text, enabled)
+    pub items: Vec<(String, bool)>,
+    pub current: usize,
+    /// Top left corner
+    pub position: Point2<f32>,
+}
+
+impl Menu {
+    pub fn new(items: Vec<(String, bool)>, position: Point2<f32>) -> Self {
+        let mut menu = Self {
+            items,
+            current: 0,
+            position,
+        };
+        if !menu.enabled(0) {
+            menu.down();
+        }
+        menu
+    }
+
+    fn enabled(&self, i: usize) -> bool {
+        self.items
+            .get(i)
+            .map(|(_, enabled)| *enabled)
+            .unwrap_or(false)
+    }
+
+    /// Previous enabled entry, wrapping around
+    pub fn up(&mut self) {
+        let len = self.items.len();
+        for step in 1..len {
+            let i = (self.current + len - step) % len;
+            if self.enabled(i) {
+                self.current = i;
+                return;
+            }
+        }
+    }
+
+    /// Next enabled entry, wrapping around
+    pub fn down(&mut self) {
+        let len = self.items.len();
+        for step in 1..len {
+            let i = (self.current + step) % len;
+            if self.enabled(i) {
+                self.current = i;
+                return;
+            }
+        }
+    }
+
+    /// Select the enabled entry under the pointer, returns whether there is one
+    pub fn point(&mut self, pointer: Point2<f32>) -> bool {
+        let x = pointer.x - self.position.x;
+        let y = pointer.y - self.position.y;
+        if !(0.0..ITEM_WIDTH).contains(&x) || y < 0.0 {
+            return false;
+        }
+        let i = (y / ITEM_HEIGHT) as usize;
+        if !self.enabled(i) {
+            return false;
+        }
+        self.current = i;
+        true
+    }
+
+    /// Entry to run on Confirm, None if nothing is enabled
+    pub fn selected(&self) -> Option<usize> {
+        if self.enabled(self.current) {
+            Some(self.current)
+        } else {
+            None
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        let highlight = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(0.0, 0.0, ITEM_WIDTH, ITEM_HEIGHT),
+            graphics::Color::from_rgba(0, 127, 127, 191),
+        )?;
+        for (i, (item, enabled)) in self.items.iter().enumerate() {
+            let y = self.position.y + i as f32 * ITEM_HEIGHT;
+            if i == self.current && *enabled {
+                graphics::draw(
+                    ctx,
+                    &highlight,
+                    graphics::DrawParam::new().dest(Point2 {
+                        x: self.position.x,
+                        y,
+                    }),
+                )?;
+            }
+            let color = if *enabled {
+                graphics::Color::from_rgb(255, 255, 255)
+            } else {
+                graphics::Color::from_rgb(127, 127, 127)
+            };
+            let text = graphics::Text::new(graphics::TextFragment::new(&item[..]).color(color));
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam::new().dest(Point2 {
+                    x: self.position.x + 16.0,
+                    y: y + 12.0,
+                }),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use crate::rendering::Rendering;
+use ggez::mint::Point2;
+use ggez::{graphics, Context, GameResult};
+use tower::core::{Controller, ExplorationSystem, Game, Position};
+
+/// Explored tiles of the map of the camera, with teleporters, the characters
+/// in sight and the view of the camera
+pub struct Minimap;
+
+impl Minimap {
+    /// Draw the map scaled to fit `bounds`, tiles at most `max_tile` wide
+    pub fn draw(
+        ctx: &mut Context,
+        game: &Game,
+        bounds: graphics::Rect,
+        max_tile: f32,
+    ) -> GameResult<()> {
+        let (map_id, center_x, center_y) = match game.camera_center() {
+            Some(center) => center,
+            None => return Ok(()),
+        };
+        let map = match game.tile_maps.get(&map_id) {
+            Some(map) => map,
+            None => return Ok(()),
+        };
+        let height = map.tiles.len();
+        let width = map.tiles.iter().map(|row| row.len()).max().unwrap_or(0);
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let size = (bounds.w / width as f32)
+            .min(bounds.h / height as f32)
+            .min(max_tile);
+        let left = bounds.x + (bounds.w - size * width as f32) / 2.0;
+        let top = bounds.y + (bounds.h - size * height as f32) / 2.0;
+        let rect = |x: f32, y: f32, scale: f32| {
+            let margin = size * (1.0 - scale) / 2.0;
+            graphics::Rect::new(
+                left + x * size + margin,
+                top + y * size + margin,
+                size * scale,
+                size * scale,
+            )
+        };
+
+        let mut mesh = graphics::MeshBuilder::new();
+        mesh.rectangle(
+            graphics::DrawMode::fill(),
+            bounds,
+            graphics::Color::from_rgba(0, 0, 0, 191),
+        );
+        let exploration = &game.exploration_system;
+        for (y, row) in map.tiles.iter().enumerate() {
+            for (x, id) in row.iter().enumerate() {
+                let position = Position {
+                    map_id,
+                    x: x as i32,
+                    y: y as i32,
+                };
+                let tile = match game.tiles.get(id) {
+                    Some(tile) if exploration.is_explored(&position) => tile,
+                    _ => continue,
+                };
+                let color = if !tile.walkable {
+                    graphics::Color::from_rgb(63, 63, 63)
+                } else if tile.hazard.is_some() {
+                    graphics::Color::from_rgb(191, 95, 0)
+                } else {
+                    graphics::Color::from_rgb(159, 159, 159)
+                };
+                mesh.rectangle(
+                    graphics::DrawMode::fill(),
+                    rect(x as f32, y as f32, 1.0),
+                    color,
+                );
+            }
+        }
+        for t in game.teleportations.values() {
+            if t.position.map_id == map_id && exploration.is_explored(&t.position) {
+                mesh.rectangle(
+                    graphics::DrawMode::fill(),
+                    rect(t.position.x as f32, t.position.y as f32, 0.6),
+                    graphics::Color::from_rgb(255, 255, 0),
+                );
+            }
+        }
+        // Players always, others only while in sight
+        for c in game.characters.values() {
+            if c.hidden || c.position.map_id != map_id {
+                continue;
+            }
+            if !matches!(c.controller, Controller::Player)
+                && !ExplorationSystem::is_visible(game, &c.position)
+            {
+                continue;
+            }
+            mesh.circle(
+                graphics::DrawMode::fill(),
+                Point2 {
+                    x: left + (c.position.x as f32 + 0.5) * size,
+                    y: top + (c.position.y as f32 + 0.5) * size,
+                },
+                (size * 0.4).max(2.0),
+                0.5,
+                Rendering::controller_color(&c.controller),
+            );
+        }
+        // View of the camera
+        let extend = game.camera.extend as f32;
+        let view = size * (extend * 2.0 + 1.0);
+        mesh.rectangle(
+            graphics::DrawMode::stroke(1.0),
+            graphics::Rect::new(
+                left + (center_x - extend) * size,
+                top + (center_y - extend) * size,
+                view,
+                view,
+            ),
+            graphics::Color::from_rgb(255, 255, 255),
+        );
+
+        let mesh = mesh.build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::new())
+    }
+}
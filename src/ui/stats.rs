@@ -1,5 +1,5 @@
 use ggez::{graphics, Context, GameResult};
-use tower::core::Character;
+use tower::core::{Character, Localization};
 
 pub struct Stats {
     pub text: graphics::Text,
@@ -7,15 +7,21 @@ pub struct Stats {
 }
 
 impl Stats {
-    pub fn new(ctx: &mut Context, character: &Character) -> GameResult<Self> {
-        let text = graphics::Text::new(format!(
-            "Hp: {}/{} \nMp: {}/{} \nAttack: {} \nDefence: {}",
-            character.hp.current(),
-            character.hp.max(),
-            character.mp.current(),
-            character.mp.max(),
-            character.attack.current(),
-            character.defence.current(),
+    pub fn new(
+        ctx: &mut Context,
+        character: &Character,
+        localization: &Localization,
+    ) -> GameResult<Self> {
+        let text = graphics::Text::new(localization.format(
+            "ui.stats",
+            &[
+                ("hp", character.hp.current().to_string()),
+                ("max_hp", character.hp.max().to_string()),
+                ("mp", character.mp.current().to_string()),
+                ("max_mp", character.mp.max().to_string()),
+                ("attack", character.attack.current().to_string()),
+                ("defence", character.defence.current().to_string()),
+            ],
         ));
         let canvas = graphics::Canvas::with_window_size(ctx)?;
         Ok(Self { text, canvas })
@@ -0,0 +1,155 @@
+use ggez::{graphics, mint::Point2, Context, GameResult};
+
+const PADDING: f32 = 16.0;
+
+/// Dialogue box revealing wrapped text page by page
+pub struct TextBox {
+    /// Wrap width in pixels
+    pub width: f32,
+    pub lines_per_page: usize,
+    pub chars_per_second: f32,
+    pages: Vec<String>,
+    page: usize,
+    /// Chars of the page shown so far
+    revealed: f32,
+    line_height: f32,
+}
+
+impl TextBox {
+    pub fn new(width: f32, lines_per_page: usize) -> Self {
+        Self {
+            width,
+            lines_per_page,
+            chars_per_second: 40.0,
+            pages: Vec::new(),
+            page: 0,
+            revealed: 0.0,
+            line_height: 0.0,
+        }
+    }
+
+    pub fn set_text(&mut self, ctx: &mut Context, text: &str) {
+        self.line_height = graphics::Text::new("A").height(ctx) as f32;
+        let lines = wrap(ctx, text, self.width);
+        self.pages = lines
+            .chunks(self.lines_per_page.max(1))
+            .map(|page| page.join("\n"))
+            .collect();
+        self.page = 0;
+        self.revealed = 0.0;
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        self.revealed += delta * self.chars_per_second;
+    }
+
+    fn page_len(&self) -> usize {
+        self.pages
+            .get(self.page)
+            .map(|page| page.chars().count())
+            .unwrap_or_default()
+    }
+
+    /// The current page is fully shown
+    pub fn is_revealed(&self) -> bool {
+        self.revealed >= self.page_len() as f32
+    }
+
+    /// The last page is fully shown
+    pub fn is_done(&self) -> bool {
+        self.page + 1 >= self.pages.len() && self.is_revealed()
+    }
+
+    pub fn reveal(&mut self) {
+        self.page = self.pages.len().saturating_sub(1);
+        self.revealed = self.page_len() as f32;
+    }
+
+    /// Finish revealing or turn the page, false once the text is done
+    pub fn advance(&mut self) -> bool {
+        if !self.is_revealed() {
+            self.revealed = self.page_len() as f32;
+            true
+        } else if self.page + 1 < self.pages.len() {
+            self.page += 1;
+            self.revealed = 0.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn height(&self) -> f32 {
+        self.line_height * self.lines_per_page as f32 + PADDING * 2.0
+    }
+
+    pub fn draw(&self, ctx: &mut Context, dest: Point2<f32>) -> GameResult<()> {
+        let bg = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(0.0, 0.0, self.width + PADDING * 2.0, self.height()),
+            graphics::Color::from_rgba(0, 0, 127, 191),
+        )?;
+        graphics::draw(ctx, &bg, graphics::DrawParam::default().dest(dest))?;
+
+        let page = self.pages.get(self.page).map(|s| &s[..]).unwrap_or("");
+        let shown: String = page.chars().take(self.revealed as usize).collect();
+        let text = graphics::Text::new(shown);
+        let text_dest = Point2 {
+            x: dest.x + PADDING,
+            y: dest.y + PADDING,
+        };
+        graphics::draw(ctx, &text, graphics::DrawParam::default().dest(text_dest))?;
+
+        // Continue indicator
+        if self.is_revealed() {
+            let indicator = if self.is_done() { "■" } else { "▼" };
+            let text = graphics::Text::new(indicator);
+            let indicator_dest = Point2 {
+                x: dest.x + self.width + PADDING,
+                y: dest.y + self.height() - PADDING - self.line_height,
+            };
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam::default().dest(indicator_dest),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Split the text into lines no wider than `width`, breaking between words
+/// and inside words too long for a line
+pub fn wrap(ctx: &mut Context, text: &str, width: f32) -> Vec<String> {
+    let fits = |ctx: &mut Context, s: &str| graphics::Text::new(s).width(ctx) as f32 <= width;
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            let candidate = if line.is_empty() {
+                String::from(word)
+            } else {
+                format!("{} {}", line, word)
+            };
+            if fits(ctx, &candidate) {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(line);
+            }
+            line = String::new();
+            for c in word.chars() {
+                line.push(c);
+                if !fits(ctx, &line) && line.chars().count() > 1 {
+                    line.pop();
+                    lines.push(line);
+                    line = c.to_string();
+                }
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,120 @@
+use crate::data::ImageCache;
+use crate::rendering::Rendering;
+use ggez::mint::Point2;
+use ggez::{graphics, Context, GameResult};
+use tower::core::{Command, Game, Target, TurnSystem};
+
+/// Turns shown
+pub const TURNS: usize = 8;
+const AVATAR_SIZE: f32 = 48.0;
+const SPACING: f32 = 64.0;
+
+/// Who acts next, avatars from left to right with their turn time
+pub struct Timeline;
+
+impl Timeline {
+    /// Draw the turn order after the character uses the action on the target,
+    /// costing what the turn system will charge
+    pub fn draw_preview(
+        ctx: &mut Context,
+        image_caches: &mut ImageCache,
+        game: &Game,
+        character_id: i32,
+        action_id: i32,
+        target: &Target,
+        dest: Point2<f32>,
+    ) -> GameResult<()> {
+        let action = match game.actions.get(&action_id) {
+            Some(action) => action,
+            None => return Ok(()),
+        };
+        let localization = &game.localization;
+        let title = localization.format(
+            "ui.timeline.preview",
+            &[("action", String::from(localization.get(&action.label)))],
+        );
+        let turn_time = Command::preview_turn_time(game, character_id, action_id, target);
+        Timeline::draw(ctx, image_caches, game, &title, Some(turn_time), dest)
+    }
+
+    /// Draw the timeline of the map of the camera from `dest`, with the
+    /// current turn costing `current_time` if given
+    pub fn draw(
+        ctx: &mut Context,
+        image_caches: &mut ImageCache,
+        game: &Game,
+        title: &str,
+        current_time: Option<i32>,
+        dest: Point2<f32>,
+    ) -> GameResult<()> {
+        let map_id = match game.characters.get(&game.camera.character_id) {
+            Some(c) => c.position.map_id,
+            None => return Ok(()),
+        };
+        let turns = TurnSystem::timeline(game, map_id, current_time, TURNS);
+
+        let bg = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(0.0, 0.0, 16.0 + SPACING * TURNS as f32, 104.0),
+            graphics::Color::from_rgba(0, 0, 0, 159),
+        )?;
+        graphics::draw(ctx, &bg, graphics::DrawParam::new().dest(dest))?;
+        let text = graphics::Text::new(title);
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::new().dest(Point2 {
+                x: dest.x + 8.0,
+                y: dest.y + 4.0,
+            }),
+        )?;
+
+        for (i, turn) in turns.iter().enumerate() {
+            let c = match game.characters.get(&turn.character_id) {
+                Some(c) => c,
+                None => continue,
+            };
+            let x = dest.x + 16.0 + i as f32 * SPACING;
+            let y = dest.y + 28.0;
+            let color = Rendering::controller_color(&c.controller);
+            let width = if i == 0 { 4.0 } else { 2.0 };
+            let border = graphics::Mesh::new_rectangle(
+                ctx,
+                graphics::DrawMode::stroke(width),
+                graphics::Rect::new(0.0, 0.0, AVATAR_SIZE, AVATAR_SIZE),
+                color,
+            )?;
+
+            if let Some(sprite) = game.character_sprites.get(&c.character_sprite_id) {
+                let image = image_caches.get(ctx, &sprite.avatar)?;
+                let scale = [
+                    AVATAR_SIZE / image.width() as f32,
+                    AVATAR_SIZE / image.height() as f32,
+                ];
+                graphics::draw(
+                    ctx,
+                    image,
+                    graphics::DrawParam::new()
+                        .dest(Point2 { x, y })
+                        .scale(scale),
+                )?;
+            }
+            graphics::draw(
+                ctx,
+                &border,
+                graphics::DrawParam::new().dest(Point2 { x, y }),
+            )?;
+            let time = graphics::Text::new(format!("+{}", turn.time));
+            graphics::draw(
+                ctx,
+                &time,
+                graphics::DrawParam::new().dest(Point2 {
+                    x,
+                    y: y + AVATAR_SIZE + 4.0,
+                }),
+            )?;
+        }
+        Ok(())
+    }
+}